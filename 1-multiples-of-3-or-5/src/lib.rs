@@ -1,3 +1,5 @@
+use euler_utils::modular::lcm;
+
 pub struct MultiplesOf {
     pub number: i32,
     pub multiples: Vec<i32>,
@@ -37,8 +39,82 @@ impl Iterator for MultiplesOf {
     }
 }
 
-#[cfg(test)]
+// The count and sum of the positive integers below a limit that are divisible by at least
+// one of a set of divisors.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct MultiplesSummary {
+    pub count: u128,
+    pub sum: u128,
+}
+
+impl MultiplesSummary {
+    // d, 2d, ..., kd with k = floor((limit - 1) / d), summed as d k (k + 1) / 2. Halving
+    // whichever of k and k + 1 is even keeps the product inside u128 for any u64 limit.
+    fn of_single_divisor(d: u64, limit: u64) -> Self {
+        let k = ((limit - 1) / d) as u128;
+        let triangle = if k.is_multiple_of(2) {
+            (k / 2) * (k + 1)
+        } else {
+            k * k.div_ceil(2)
+        };
+
+        MultiplesSummary {
+            count: k,
+            sum: triangle * d as u128,
+        }
+    }
+}
+
+// Zero and anything at or past the limit contribute nothing, and a divisor that is a multiple
+// of another divisor adds nothing to the union, so both are dropped before recursing.
+fn useful_divisors(divisors: &[u64], limit: u64) -> Vec<u64> {
+    let mut candidates = divisors
+        .iter()
+        .copied()
+        .filter(|&d| d != 0 && d < limit)
+        .collect::<Vec<u64>>();
+    candidates.sort_unstable();
+    candidates.dedup();
+
+    let mut useful: Vec<u64> = Vec::with_capacity(candidates.len());
+    for d in candidates {
+        if !useful.iter().any(|&u| d.is_multiple_of(u)) {
+            useful.push(d);
+        }
+    }
+    useful
+}
+
+// Inclusion-exclusion one divisor at a time: |A ∪ B| = |A| + |B| - |A ∩ B|, where B is the
+// union over the remaining divisors and A ∩ B is the union of multiples of lcm(d, e) for each
+// remaining e. Every intermediate value is the summary of a real union below the limit, so
+// nothing goes negative and nothing exceeds twice the sum of 1..limit.
+fn union_below(divisors: &[u64], limit: u64) -> MultiplesSummary {
+    let Some((&d, rest)) = divisors.split_first() else {
+        return MultiplesSummary::default();
+    };
+
+    let overlaps = rest.iter().filter_map(|&e| lcm(d, e)).collect::<Vec<u64>>();
 
+    let single = MultiplesSummary::of_single_divisor(d, limit);
+    let others = union_below(rest, limit);
+    let both = union_below(&useful_divisors(&overlaps, limit), limit);
+
+    MultiplesSummary {
+        count: single.count + others.count - both.count,
+        sum: single.sum + others.sum - both.sum,
+    }
+}
+
+pub fn multiples_below(divisors: &[u64], limit: u64) -> MultiplesSummary {
+    if limit == 0 {
+        return MultiplesSummary::default();
+    }
+
+    union_below(&useful_divisors(divisors, limit), limit)
+}
+
+#[cfg(test)]
 mod tests {
     use super::*;
 
@@ -133,4 +209,61 @@ mod tests {
 
         assert_eq!(sum_mults, 23);
     }
+
+    fn brute_force(divisors: &[u64], limit: u64) -> MultiplesSummary {
+        let matching = (1..limit)
+            .filter(|&n| divisors.iter().any(|&d| d != 0 && n.is_multiple_of(d)))
+            .collect::<Vec<u64>>();
+
+        MultiplesSummary {
+            count: matching.len() as u128,
+            sum: matching.iter().map(|&n| n as u128).sum(),
+        }
+    }
+
+    #[test]
+    fn test_multiples_below_from_problem_statement() {
+        assert_eq!(
+            multiples_below(&[3, 5], 10),
+            MultiplesSummary { count: 4, sum: 23 }
+        );
+        assert_eq!(multiples_below(&[3, 5], 1000).sum, 233_168);
+    }
+
+    #[test]
+    fn test_multiples_below_matches_brute_force() {
+        let sets: [&[u64]; 8] = [
+            &[],
+            &[1],
+            &[7],
+            &[4, 6],
+            &[2, 4, 8],
+            &[6, 10, 15],
+            &[0, 3, 3, 5, 500],
+            &[2, 3, 5, 7, 11, 13, 17],
+        ];
+
+        sets.iter().for_each(|divisors| {
+            [0, 1, 2, 30, 499, 1000].iter().for_each(|&limit| {
+                assert_eq!(
+                    multiples_below(divisors, limit),
+                    brute_force(divisors, limit),
+                    "{:?} below {}",
+                    divisors,
+                    limit
+                );
+            });
+        });
+    }
+
+    #[test]
+    fn test_multiples_below_huge_limits() {
+        let summary = multiples_below(&[3, 5], 1_000_000_000_000_000_000);
+        assert_eq!(summary.count, 466_666_666_666_666_666);
+        assert_eq!(summary.sum, 233_333_333_333_333_333_166_666_666_666_666_668);
+
+        let everything = multiples_below(&[1], u64::MAX);
+        let n = u64::MAX as u128 - 1;
+        assert_eq!(everything.sum, n / 2 * (n + 1));
+    }
 }
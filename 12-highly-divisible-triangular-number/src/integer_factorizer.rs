@@ -1,4 +1,5 @@
-use crate::prime_generator::PrimeGenerator;
+use euler_utils::factor::factorize;
+use euler_utils::primality;
 
 #[derive(Debug, PartialEq)]
 pub struct IntegerFactorizer {
@@ -15,21 +16,22 @@ impl IntegerFactorizer {
     }
 
     pub fn is_prime(&self) -> bool {
-        let mut p = PrimeGenerator::new();
-        p.get_primes_below_n(self.k + 1);
-        p.is_prime(self.k)
+        primality::is_prime(self.k as u64)
     }
 
     pub fn prime_factors(&self) -> Vec<usize> {
-        let sqrt = self.sqrt_ceiling();
-        let mut p = PrimeGenerator::new();
-        p.get_primes_below_n(sqrt);
-        p.primes
+        self.prime_factorization()
             .iter()
-            .map(|m| *m)
-            .filter(|&prime| self.k % prime == 0)
+            .map(|&(prime, _)| prime)
             .collect::<Vec<usize>>()
     }
+
+    pub fn prime_factorization(&self) -> Vec<(usize, u32)> {
+        factorize(self.k as u64)
+            .iter()
+            .map(|&(prime, exponent)| (prime as usize, exponent))
+            .collect::<Vec<(usize, u32)>>()
+    }
 }
 
 #[cfg(test)]
@@ -70,6 +72,14 @@ mod tests {
     #[test]
     fn can_get_prime_factors_of_integers_below_20() {
         assert_eq!(get_prime_factors_of_integer(2), vec![2]);
-        // assert_eq!(get_prime_factors_of_integer(3), vec![3]);
+        assert_eq!(get_prime_factors_of_integer(3), vec![3]);
+        assert_eq!(get_prime_factors_of_integer(12), vec![2, 3]);
+        assert_eq!(get_prime_factors_of_integer(14), vec![2, 7]);
+    }
+
+    #[test]
+    fn prime_factorization_keeps_multiplicities() {
+        let f = IntegerFactorizer::new(720);
+        assert_eq!(f.prime_factorization(), vec![(2, 4), (3, 2), (5, 1)]);
     }
 }
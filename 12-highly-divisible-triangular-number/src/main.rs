@@ -1,6 +1,6 @@
-use highly_divisible_triangular_number::triangular_numbers::get_first_with_n_divisors;
+use euler_utils::triangular_numbers::first_triangular_with_more_divisors_than;
 
 fn main() {
-    let result = get_first_with_n_divisors(500);
+    let (_, result) = first_triangular_with_more_divisors_than(500);
     println!("Result: {}", result);
 }
@@ -1,3 +1,5 @@
+use euler_utils::big_uint::BigUint;
+use euler_utils::digits::decimal_str_digits;
 use std::str::FromStr;
 
 #[derive(Debug, PartialEq)]
@@ -26,76 +28,38 @@ impl NumVec {
     }
 
     pub fn n_from_start(&self, n: usize) -> u32 {
-        let digit = self.0.chars().nth(n).unwrap();
-        digit.to_digit(10).unwrap()
+        assert!(n < self.len(), "Digit index out of range");
+        decimal_str_digits(&self.0[n..=n])
+            .next()
+            .expect("Index is inside the number")
     }
 
     pub fn n_from_end(&self, n: usize) -> u32 {
-        let len = self.len();
-        let index = len - n - 1;
-        let digit = self.0.chars().nth(index).unwrap();
-        digit.to_digit(10).unwrap()
+        assert!(n < self.len(), "Digit index out of range");
+        self.n_from_start(self.len() - n - 1)
     }
 
     pub fn to_vec_with_size(&self, size: usize) -> Vec<u32> {
+        assert!(size >= self.len(), "Size is smaller than the number");
         let mut vec = vec![0; size];
-        for i in 0..self.len() {
-            vec[size - self.len() + i] = self.n_from_start(i);
-        }
+        vec[size - self.len()..]
+            .iter_mut()
+            .zip(decimal_str_digits(&self.0))
+            .for_each(|(slot, digit)| *slot = digit);
         vec
     }
 
-    pub fn add_digits(nums: Vec<Self>) -> Self {
-        let len = nums.iter().map(|n| n.len()).max().unwrap();
-
-        let vecs = nums
-            .iter()
-            .map(|n| n.to_vec_with_size(len))
-            .collect::<Vec<Vec<u32>>>();
-
-        let mut sum_vector = vec![0; len];
-        vecs.iter().for_each(|vec| {
-            sum_vector = Self::get_element_wise_sum_vector(len, sum_vector.clone(), vec.clone());
-        });
-
-        let result = Self::convert_sum_vector_to_sum_string(len, &mut sum_vector);
-
-        NumVec::from_str(&result).expect("Failed to create NumVec from str")
-    }
-
-    fn get_element_wise_sum_vector(len: usize, vec1: Vec<u32>, vec2: Vec<u32>) -> Vec<u32> {
-        let mut sum_vector = vec![0; len];
-        (0..len).rev().for_each(|i| {
-            sum_vector[i] += vec1[i] + vec2[i];
-        });
-        sum_vector
-    }
-
-    fn convert_sum_vector_to_sum_string(len: usize, sum_vector: &mut Vec<u32>) -> String {
-        let mut sum_overflow = 0;
-        let mut sum_overflow_str: String = "".to_string();
-
-        (0..len).rev().for_each(|i| {
-            while sum_vector[i] >= 10 {
-                sum_vector[i] -= 10;
-                if i == 0 {
-                    sum_overflow += 1;
-                } else {
-                    sum_vector[i - 1] += 1;
-                }
-            }
-        });
-
-        if sum_overflow != 0 {
-            sum_overflow_str = sum_overflow.to_string();
+    // An empty NumVec, such as one the iterator has drained, is zero.
+    pub fn to_big_uint(&self) -> BigUint {
+        if self.is_empty() {
+            return BigUint::zero();
         }
+        self.0.parse().expect("NumVec holds only digits")
+    }
 
-        let result = sum_overflow_str
-            + &sum_vector
-                .iter()
-                .map(|&d| d.to_string())
-                .collect::<String>();
-        result
+    pub fn add_digits(nums: Vec<Self>) -> Self {
+        let sum = nums.iter().map(NumVec::to_big_uint).sum::<BigUint>();
+        NumVec::new(sum.to_string())
     }
 }
 
@@ -122,7 +86,6 @@ impl FromStr for NumVec {
 }
 
 #[cfg(test)]
-
 mod tests {
     use super::*;
 
@@ -325,4 +288,32 @@ mod tests {
 
         assert_eq!(result, expected);
     }
+
+    #[test]
+    fn empty_numvec_is_zero() {
+        let empty = NumVec::new(String::new());
+        assert_eq!(empty.to_big_uint(), BigUint::zero());
+        assert_eq!(
+            NumVec::add_digits(vec![empty, NumVec::new("12".to_owned())]),
+            NumVec::new("12".to_owned())
+        );
+        assert_eq!(NumVec::add_digits(vec![]), NumVec::new("0".to_owned()));
+    }
+
+    #[test]
+    fn leading_zeros_keep_their_places() {
+        let n = NumVec::new("0042".to_owned());
+        assert_eq!(n.n_from_start(0), 0);
+        assert_eq!(n.n_from_start(2), 4);
+        assert_eq!(n.n_from_end(0), 2);
+        assert_eq!(n.to_vec_with_size(6), vec![0, 0, 0, 0, 4, 2]);
+    }
+
+    #[test]
+    fn can_convert_to_big_uint() {
+        let (n1, _) = nums();
+        let num_vec = NumVec::new(n1.clone());
+
+        assert_eq!(num_vec.to_big_uint().to_string(), n1);
+    }
 }
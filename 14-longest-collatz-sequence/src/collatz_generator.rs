@@ -1,90 +1,184 @@
-pub fn get_collatz_sequence_len(n: u64) -> usize {
+use euler_utils::big_uint::BigUint;
+use euler_utils::integer::UnsignedInt;
+use std::fmt::{Debug, Display};
+use std::iter::FusedIterator;
+
+// What a Collatz step needs from its integer type. Primitives check the odd step for overflow;
+// `BigUint` never overflows, which makes it the choice for trajectories that leave u128.
+pub trait CollatzInt: Clone + Ord + Debug + Display {
+    fn is_zero(&self) -> bool;
+    fn is_one(&self) -> bool;
+    fn is_even(&self) -> bool;
+    fn half(&self) -> Self;
+    fn checked_triple_plus_one(&self) -> Option<Self>;
+}
+
+macro_rules! impl_collatz_int {
+    ($($t:ty),*) => {
+        $(
+            impl CollatzInt for $t {
+                fn is_zero(&self) -> bool {
+                    *self == 0
+                }
+
+                fn is_one(&self) -> bool {
+                    *self == 1
+                }
+
+                fn is_even(&self) -> bool {
+                    UnsignedInt::is_even(*self)
+                }
+
+                fn half(&self) -> Self {
+                    *self / 2
+                }
+
+                fn checked_triple_plus_one(&self) -> Option<Self> {
+                    UnsignedInt::checked_mul(*self, 3)
+                        .and_then(|tripled| UnsignedInt::checked_add(tripled, 1))
+                }
+            }
+        )*
+    };
+}
+
+impl_collatz_int!(u8, u16, u32, u64, u128, usize);
+
+impl CollatzInt for BigUint {
+    fn is_zero(&self) -> bool {
+        BigUint::is_zero(self)
+    }
+
+    fn is_one(&self) -> bool {
+        *self == BigUint::one()
+    }
+
+    fn is_even(&self) -> bool {
+        self % 2 == 0
+    }
+
+    fn half(&self) -> Self {
+        self / 2
+    }
+
+    fn checked_triple_plus_one(&self) -> Option<Self> {
+        Some(&(self * 3) + &BigUint::one())
+    }
+}
+
+pub type BigCollatzGenerator = CollatzGenerator<BigUint>;
+
+pub fn get_collatz_sequence_len<T: CollatzInt>(n: T) -> Result<usize, String> {
     let mut collatz = CollatzGenerator::new(n);
     collatz.len()
 }
 
+// Steps that would overflow T return an error and leave the generator where it was, so once
+// a step has overflowed every later step reports the same overflow. A start of 0, which would
+// halve to itself forever, is an error in the same way. The iterator yields the error once
+// and then ends.
 #[derive(Debug, PartialEq)]
-pub struct CollatzGenerator {
-    starting_number: u64,
-    current_number: u64,
+pub struct CollatzGenerator<T: CollatzInt> {
+    starting_number: T,
+    current_number: T,
+    failed: bool,
 }
 
-impl CollatzGenerator {
-    pub fn new(starting_number: u64) -> Self {
+impl<T: CollatzInt> CollatzGenerator<T> {
+    pub fn new(starting_number: T) -> Self {
         CollatzGenerator {
+            current_number: starting_number.clone(),
             starting_number,
-            current_number: starting_number,
+            failed: false,
         }
     }
 
     pub fn is_even(&self) -> bool {
-        self.current_number % 2 == 0
+        self.current_number.is_even()
     }
 
     pub fn is_odd(&self) -> bool {
         !self.is_even()
     }
 
-    pub fn calculate_next_term_when_even(&mut self) -> Option<u64> {
-        self.current_number /= 2;
-        Some(self.current_number)
+    pub fn calculate_next_term_when_even(&mut self) -> Option<T> {
+        self.current_number = self.current_number.half();
+        Some(self.current_number.clone())
     }
 
-    pub fn calculate_next_term_when_odd(&mut self) -> Option<u64> {
-        self.current_number *= 3;
-        self.current_number += 1;
-        Some(self.current_number)
+    pub fn calculate_next_term_when_odd(&mut self) -> Result<T, String> {
+        self.current_number = self
+            .current_number
+            .checked_triple_plus_one()
+            .ok_or_else(|| {
+                format!(
+                    "Collatz term 3 * {} + 1 overflows {}",
+                    self.current_number,
+                    std::any::type_name::<T>()
+                )
+            })?;
+        Ok(self.current_number.clone())
     }
 
-    pub fn calculate_next_term(&mut self) -> Option<u64> {
-        if self.current_number == 1 {
-            None
+    pub fn calculate_next_term(&mut self) -> Result<Option<T>, String> {
+        if self.current_number.is_zero() {
+            Err("Collatz trajectories start at 1 or above".to_string())
+        } else if self.current_number.is_one() {
+            Ok(None)
         } else if self.is_even() {
-            self.calculate_next_term_when_even()
+            Ok(self.calculate_next_term_when_even())
         } else {
-            self.calculate_next_term_when_odd()
+            self.calculate_next_term_when_odd().map(Some)
         }
     }
 
-    pub fn get_collatz_sequence(&mut self) -> Vec<u64> {
-        let mut sequence = vec![self.starting_number];
-        while let Some(next_term) = self.calculate_next_term() {
+    pub fn get_collatz_sequence(&mut self) -> Result<Vec<T>, String> {
+        let mut sequence = vec![self.starting_number.clone()];
+        while let Some(next_term) = self.calculate_next_term()? {
             sequence.push(next_term);
         }
-        sequence
+        Ok(sequence)
     }
 
-    pub fn len(&mut self) -> usize {
-        self.get_collatz_sequence().len()
+    pub fn len(&mut self) -> Result<usize, String> {
+        self.get_collatz_sequence().map(|sequence| sequence.len())
     }
 
     #[must_use]
     pub fn is_empty(&mut self) -> bool {
-        self.len() == 0
+        matches!(self.len(), Ok(0))
     }
 }
 
-impl Iterator for CollatzGenerator {
-    type Item = u64;
+impl<T: CollatzInt> Iterator for CollatzGenerator<T> {
+    type Item = Result<T, String>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.calculate_next_term()
+        if self.failed {
+            return None;
+        }
+
+        let next = self.calculate_next_term();
+        self.failed = next.is_err();
+        next.transpose()
     }
 }
 
-#[cfg(test)]
+impl<T: CollatzInt> FusedIterator for CollatzGenerator<T> {}
 
+#[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_new() {
-        let collatz = CollatzGenerator::new(10);
+        let collatz = CollatzGenerator::<u64>::new(10);
         assert_eq!(collatz.starting_number, 10);
 
-        let collatz2 = CollatzGenerator {
+        let collatz2 = CollatzGenerator::<u64> {
             starting_number: 10,
             current_number: 10,
+            failed: false,
         };
 
         assert_eq!(collatz, collatz2);
@@ -92,25 +186,25 @@ mod tests {
 
     #[test]
     fn can_test_is_even() {
-        let collatz = CollatzGenerator::new(10);
+        let collatz = CollatzGenerator::<u64>::new(10);
         assert!(collatz.is_even());
 
-        let collatz = CollatzGenerator::new(11);
+        let collatz = CollatzGenerator::<u64>::new(11);
         assert!(!collatz.is_even());
     }
 
     #[test]
     fn can_test_is_odd() {
-        let collatz = CollatzGenerator::new(10);
+        let collatz = CollatzGenerator::<u64>::new(10);
         assert!(!collatz.is_odd());
 
-        let collatz = CollatzGenerator::new(11);
+        let collatz = CollatzGenerator::<u64>::new(11);
         assert!(collatz.is_odd());
     }
 
     #[test]
     fn can_calculate_next_term_when_even() {
-        let mut collatz = CollatzGenerator::new(10);
+        let mut collatz = CollatzGenerator::<u64>::new(10);
         assert_eq!(collatz.current_number, 10);
 
         assert_eq!(collatz.calculate_next_term_when_even(), Some(5));
@@ -118,63 +212,141 @@ mod tests {
 
     #[test]
     fn can_calculate_next_term_when_odd() {
-        let mut collatz = CollatzGenerator::new(11);
+        let mut collatz = CollatzGenerator::<u64>::new(11);
         assert_eq!(collatz.current_number, 11);
 
-        assert_eq!(collatz.calculate_next_term_when_odd(), Some(11 * 3 + 1));
+        assert_eq!(collatz.calculate_next_term_when_odd(), Ok(11 * 3 + 1));
     }
 
     #[test]
     fn can_calculate_next_term() {
-        let mut collatz = CollatzGenerator::new(10);
+        let mut collatz = CollatzGenerator::<u64>::new(10);
         assert_eq!(collatz.current_number, 10);
 
-        assert_eq!(collatz.calculate_next_term(), Some(5));
-        assert_eq!(collatz.calculate_next_term(), Some(16));
-        assert_eq!(collatz.calculate_next_term(), Some(8));
-        assert_eq!(collatz.calculate_next_term(), Some(4));
-        assert_eq!(collatz.calculate_next_term(), Some(2));
-        assert_eq!(collatz.calculate_next_term(), Some(1));
+        assert_eq!(collatz.calculate_next_term(), Ok(Some(5)));
+        assert_eq!(collatz.calculate_next_term(), Ok(Some(16)));
+        assert_eq!(collatz.calculate_next_term(), Ok(Some(8)));
+        assert_eq!(collatz.calculate_next_term(), Ok(Some(4)));
+        assert_eq!(collatz.calculate_next_term(), Ok(Some(2)));
+        assert_eq!(collatz.calculate_next_term(), Ok(Some(1)));
     }
 
     #[test]
     fn calculate_next_term_returns_none_when_current_value_is_1() {
-        let mut collatz = CollatzGenerator::new(2);
+        let mut collatz = CollatzGenerator::<u64>::new(2);
         assert_eq!(collatz.current_number, 2);
-        assert_eq!(collatz.calculate_next_term(), Some(1));
-        assert_eq!(collatz.calculate_next_term(), None);
+        assert_eq!(collatz.calculate_next_term(), Ok(Some(1)));
+        assert_eq!(collatz.calculate_next_term(), Ok(None));
     }
 
     #[test]
     fn can_get_collatz_sequence() {
-        let mut collatz = CollatzGenerator::new(10);
-        assert_eq!(collatz.get_collatz_sequence(), vec![10, 5, 16, 8, 4, 2, 1]);
+        let mut collatz = CollatzGenerator::<u64>::new(10);
+        assert_eq!(
+            collatz.get_collatz_sequence(),
+            Ok(vec![10, 5, 16, 8, 4, 2, 1])
+        );
 
-        let mut collatz = CollatzGenerator::new(13);
+        let mut collatz = CollatzGenerator::<u64>::new(13);
         assert_eq!(
             collatz.get_collatz_sequence(),
-            vec![13, 40, 20, 10, 5, 16, 8, 4, 2, 1]
+            Ok(vec![13, 40, 20, 10, 5, 16, 8, 4, 2, 1])
         );
     }
 
     #[test]
     fn can_iterate() {
-        let collatz = CollatzGenerator::new(10);
-        assert_eq!(collatz.collect::<Vec<u64>>(), vec![5, 16, 8, 4, 2, 1]);
+        let collatz = CollatzGenerator::<u64>::new(10);
+        assert_eq!(
+            collatz.collect::<Result<Vec<u64>, String>>(),
+            Ok(vec![5, 16, 8, 4, 2, 1])
+        );
 
-        let collatz = CollatzGenerator::new(13);
+        let collatz = CollatzGenerator::<u64>::new(13);
         assert_eq!(
-            collatz.collect::<Vec<u64>>(),
-            vec![40, 20, 10, 5, 16, 8, 4, 2, 1]
+            collatz.collect::<Result<Vec<u64>, String>>(),
+            Ok(vec![40, 20, 10, 5, 16, 8, 4, 2, 1])
         );
     }
 
     #[test]
     fn can_get_len_of_collatz_sequence() {
-        let mut collatz = CollatzGenerator::new(10);
-        assert_eq!(collatz.len(), 7);
+        let mut collatz = CollatzGenerator::<u64>::new(10);
+        assert_eq!(collatz.len(), Ok(7));
 
-        let mut collatz = CollatzGenerator::new(13);
-        assert_eq!(collatz.len(), 10);
+        let mut collatz = CollatzGenerator::<u64>::new(13);
+        assert_eq!(collatz.len(), Ok(10));
+    }
+
+    #[test]
+    fn odd_step_reports_overflow() {
+        let mut collatz = CollatzGenerator::new(u64::MAX);
+        assert!(collatz.calculate_next_term().is_err());
+        assert_eq!(collatz.current_number, u64::MAX);
+        assert!(collatz.calculate_next_term().is_err());
+
+        let mut collatz = CollatzGenerator::new(85u8);
+        assert_eq!(
+            collatz.calculate_next_term(),
+            Err("Collatz term 3 * 85 + 1 overflows u8".to_string())
+        );
+    }
+
+    #[test]
+    fn iterator_ends_after_reporting_overflow() {
+        let terms = CollatzGenerator::new(u64::MAX).collect::<Vec<Result<u64, String>>>();
+        assert_eq!(terms.len(), 1);
+        assert!(terms[0].is_err());
+
+        assert_eq!(CollatzGenerator::new(u64::MAX).count(), 1);
+
+        let mut collatz = CollatzGenerator::new(u64::MAX - 1);
+        assert_eq!(collatz.next(), Some(Ok(u64::MAX / 2)));
+        assert!(collatz.next().is_some_and(|term| term.is_err()));
+        assert_eq!(collatz.next(), None);
+        assert_eq!(collatz.next(), None);
+    }
+
+    #[test]
+    fn zero_start_is_an_error() {
+        let error = "Collatz trajectories start at 1 or above".to_string();
+        assert_eq!(get_collatz_sequence_len(0u64), Err(error.clone()));
+        assert_eq!(
+            get_collatz_sequence_len(BigUint::zero()),
+            Err(error.clone())
+        );
+        assert_eq!(
+            CollatzGenerator::new(0u32).get_collatz_sequence(),
+            Err(error.clone())
+        );
+
+        let terms = CollatzGenerator::new(0u8).collect::<Vec<Result<u8, String>>>();
+        assert_eq!(terms, vec![Err(error)]);
+    }
+
+    #[test]
+    fn wider_types_follow_trajectories_past_u64() {
+        let start = u64::MAX - 2;
+        assert!(get_collatz_sequence_len(start).is_err());
+
+        let narrow = get_collatz_sequence_len(start as u128);
+        let big = get_collatz_sequence_len(BigUint::from(start));
+        assert!(narrow.is_ok());
+        assert_eq!(narrow, big);
+    }
+
+    #[test]
+    fn big_integers_never_overflow() {
+        let start = BigUint::from(u128::MAX);
+        let terms = BigCollatzGenerator::new(start.clone())
+            .collect::<Result<Vec<BigUint>, String>>()
+            .unwrap();
+
+        assert!(terms[0] > start);
+        assert_eq!(terms.last(), Some(&BigUint::one()));
+        assert_eq!(
+            get_collatz_sequence_len(BigUint::from(13u32)),
+            get_collatz_sequence_len(13u64)
+        );
     }
 }
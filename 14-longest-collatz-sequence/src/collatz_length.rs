@@ -0,0 +1,161 @@
+use rayon::prelude::*;
+
+// Collatz sequence lengths, counting both the start and the final 1, so 13 has length 10.
+// Lengths below `bound` live in a dense table; a larger start is walked until it drops below
+// the bound and then finished with one lookup.
+#[derive(Debug, PartialEq, Clone)]
+pub struct CollatzLengthCache {
+    lengths: Vec<u16>,
+}
+
+// Takes one shortcut step from n and returns the new value along with how many terms of the
+// ordinary sequence it skipped: every trailing zero at once for even n, and (3n + 1) / 2,
+// which is always an integer, for odd n. (3n + 1) / 2 is computed as n + (n + 1) / 2 so that
+// it only overflows when the result itself does not fit, and then it panics rather than wraps.
+fn shortcut_step(n: u64) -> (u64, u32) {
+    if n.is_multiple_of(2) {
+        let zeros = n.trailing_zeros();
+        (n >> zeros, zeros)
+    } else {
+        let next = n
+            .checked_add(n.div_ceil(2))
+            .expect("Collatz trajectory overflowed u64");
+        (next, 2)
+    }
+}
+
+impl CollatzLengthCache {
+    // The table is filled in doubling blocks [filled, 2 filled), each in parallel. A start in
+    // the block only has to be followed until it drops below the block, since everything
+    // there is already in the table.
+    pub fn new(bound: usize) -> Self {
+        assert!(bound >= 2, "Bound must be at least 2");
+
+        let mut lengths = vec![0u16; bound];
+        lengths[1] = 1;
+        let mut filled = 2;
+        while filled < bound {
+            let end = (2 * filled).min(bound);
+            let (known, rest) = lengths.split_at_mut(filled);
+            rest[..end - filled]
+                .par_iter_mut()
+                .enumerate()
+                .for_each(|(i, entry)| {
+                    let (mut n, mut steps) = ((filled + i) as u64, 0);
+                    while n >= filled as u64 {
+                        let (next, skipped) = shortcut_step(n);
+                        n = next;
+                        steps += skipped;
+                    }
+                    *entry = Self::to_entry(steps + known[n as usize] as u32);
+                });
+            filled = end;
+        }
+
+        CollatzLengthCache { lengths }
+    }
+
+    fn to_entry(length: u32) -> u16 {
+        u16::try_from(length).expect("Collatz length does not fit in the table")
+    }
+
+    pub fn bound(&self) -> usize {
+        self.lengths.len()
+    }
+
+    pub fn length(&self, start: u64) -> u32 {
+        assert!(start >= 1, "Collatz sequences start at 1 or above");
+
+        let bound = self.bound() as u64;
+        let (mut n, mut steps) = (start, 0);
+        while n >= bound {
+            let (next, skipped) = shortcut_step(n);
+            n = next;
+            steps += skipped;
+        }
+        steps + self.lengths[n as usize] as u32
+    }
+
+    // The start below `limit` with the longest sequence, the smallest such start on a tie,
+    // and that length. Any n < limit / 2 loses to 2n, which is one term longer, so only the
+    // top half of the range is searched.
+    pub fn longest_under(&self, limit: u64) -> Option<(u64, u32)> {
+        ((limit / 2).max(1)..limit)
+            .into_par_iter()
+            .map(|start| (start, self.length(start)))
+            .reduce_with(|a, b| {
+                if b.1 > a.1 || (b.1 == a.1 && b.0 < a.0) {
+                    b
+                } else {
+                    a
+                }
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collatz_generator::get_collatz_sequence_len;
+
+    #[test]
+    fn lengths_match_the_generator() {
+        let cache = CollatzLengthCache::new(100);
+        (1..10_000u64).for_each(|n| {
+            assert_eq!(
+                Ok(cache.length(n) as usize),
+                get_collatz_sequence_len(n),
+                "length of {}",
+                n
+            )
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "overflowed u64")]
+    fn length_panics_instead_of_wrapping() {
+        CollatzLengthCache::new(16).length(u64::MAX);
+    }
+
+    #[test]
+    fn can_find_longest_under_small_limits() {
+        let cache = CollatzLengthCache::new(1000);
+        assert_eq!(cache.longest_under(0), None);
+        assert_eq!(cache.longest_under(1), None);
+        assert_eq!(cache.longest_under(2), Some((1, 1)));
+        assert_eq!(cache.longest_under(10), Some((9, 20)));
+        assert_eq!(cache.longest_under(28), Some((27, 112)));
+    }
+
+    #[test]
+    fn problem_14_longest_under_one_million() {
+        let cache = CollatzLengthCache::new(1 << 16);
+        assert_eq!(cache.longest_under(1_000_000), Some((837_799, 525)));
+    }
+
+    #[test]
+    fn table_matches_the_generator_across_block_edges() {
+        let cache = CollatzLengthCache::new(1000);
+        (1..1000u64).for_each(|n| {
+            assert_eq!(
+                Ok(cache.lengths[n as usize] as usize),
+                get_collatz_sequence_len(n),
+                "length of {}",
+                n
+            )
+        });
+    }
+
+    #[ignore] // Builds a 10^8 table, so only worth running with --release
+    #[test]
+    fn can_find_longest_under_one_hundred_million() {
+        let cache = CollatzLengthCache::new(100_000_000);
+        assert_eq!(cache.longest_under(100_000_000), Some((63_728_127, 950)));
+    }
+
+    #[test]
+    fn can_find_longest_under_ten_million() {
+        let cache = CollatzLengthCache::new(1 << 20);
+        assert_eq!(cache.longest_under(10_000_000), Some((8_400_511, 686)));
+    }
+}
@@ -0,0 +1,203 @@
+use crate::collatz_generator::{CollatzGenerator, CollatzInt};
+use std::ops::Range;
+
+// Everything about one trajectory down to 1, counted in ordinary steps: n / 2 is an even step
+// and 3n + 1 an odd one. The stopping time is the first step that lands below the start,
+// which never happens for 1.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct CollatzStats<T: CollatzInt> {
+    pub start: T,
+    pub total_stopping_time: u64,
+    pub stopping_time: Option<u64>,
+    pub peak: T,
+    pub odd_steps: u64,
+    pub even_steps: u64,
+}
+
+impl<T: CollatzInt> CollatzStats<T> {
+    // Fails if the trajectory overflows T, as the generator does, or starts at 0, which halves
+    // to itself forever.
+    pub fn new(start: T) -> Result<Self, String> {
+        if start.is_zero() {
+            return Err("Collatz trajectories start at 1 or above".to_string());
+        }
+
+        let mut stats = CollatzStats {
+            start: start.clone(),
+            total_stopping_time: 0,
+            stopping_time: None,
+            peak: start.clone(),
+            odd_steps: 0,
+            even_steps: 0,
+        };
+
+        let mut previous = start.clone();
+        for term in CollatzGenerator::new(start) {
+            let term = term?;
+
+            if previous.is_even() {
+                stats.even_steps += 1;
+            } else {
+                stats.odd_steps += 1;
+            }
+            stats.total_stopping_time += 1;
+
+            if stats.stopping_time.is_none() && term < stats.start {
+                stats.stopping_time = Some(stats.total_stopping_time);
+            }
+            if term > stats.peak {
+                stats.peak = term.clone();
+            }
+            previous = term;
+        }
+
+        Ok(stats)
+    }
+}
+
+// The starts in `range` whose key beats every earlier start in the range, in order.
+fn records<T, K>(
+    range: Range<u64>,
+    key: impl Fn(&CollatzStats<T>) -> K,
+) -> Result<Vec<CollatzStats<T>>, String>
+where
+    T: CollatzInt + From<u64>,
+    K: Ord,
+{
+    let mut holders = Vec::new();
+    let mut best = None;
+
+    for start in range {
+        let stats = CollatzStats::new(T::from(start))?;
+        let value = key(&stats);
+        if best.as_ref().is_none_or(|best| value > *best) {
+            best = Some(value);
+            holders.push(stats);
+        }
+    }
+
+    Ok(holders)
+}
+
+// Starts that climb higher than any smaller start in the range. Use u128 or `BigUint` for T
+// once the peaks outgrow u64.
+pub fn path_records<T: CollatzInt + From<u64>>(
+    range: Range<u64>,
+) -> Result<Vec<CollatzStats<T>>, String> {
+    records(range, |stats: &CollatzStats<T>| stats.peak.clone())
+}
+
+// Starts that take more steps to reach 1 than any smaller start in the range.
+pub fn delay_records<T: CollatzInt + From<u64>>(
+    range: Range<u64>,
+) -> Result<Vec<CollatzStats<T>>, String> {
+    records(range, |stats: &CollatzStats<T>| stats.total_stopping_time)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use euler_utils::big_uint::BigUint;
+
+    #[test]
+    fn stats_for_27() {
+        let stats = CollatzStats::new(27u64).unwrap();
+        assert_eq!(stats.total_stopping_time, 111);
+        assert_eq!(stats.stopping_time, Some(96));
+        assert_eq!(stats.peak, 9232);
+        assert_eq!(stats.odd_steps, 41);
+        assert_eq!(stats.even_steps, 70);
+    }
+
+    #[test]
+    fn stats_for_trivial_starts() {
+        let one = CollatzStats::new(1u32).unwrap();
+        assert_eq!(one.total_stopping_time, 0);
+        assert_eq!(one.stopping_time, None);
+        assert_eq!(one.peak, 1);
+
+        let eight = CollatzStats::new(8u32).unwrap();
+        assert_eq!(eight.stopping_time, Some(1));
+        assert_eq!((eight.odd_steps, eight.even_steps), (0, 3));
+    }
+
+    #[test]
+    fn stats_agree_with_the_generator_and_across_types() {
+        (1..2000u64).for_each(|n| {
+            let stats = CollatzStats::new(n).unwrap();
+            assert_eq!(
+                Ok(stats.total_stopping_time as usize + 1),
+                crate::collatz_generator::get_collatz_sequence_len(n)
+            );
+            assert_eq!(
+                stats.odd_steps + stats.even_steps,
+                stats.total_stopping_time
+            );
+
+            let big = CollatzStats::new(BigUint::from(n)).unwrap();
+            assert_eq!(big.peak, BigUint::from(stats.peak));
+            assert_eq!(big.stopping_time, stats.stopping_time);
+        });
+    }
+
+    #[test]
+    fn stats_report_overflow() {
+        assert!(CollatzStats::new(u64::MAX).is_err());
+        assert!(CollatzStats::new(u64::MAX as u128).is_ok());
+    }
+
+    #[test]
+    fn zero_start_is_an_error() {
+        assert_eq!(
+            CollatzStats::new(0u64),
+            Err("Collatz trajectories start at 1 or above".to_string())
+        );
+        assert!(CollatzStats::new(BigUint::zero()).is_err());
+        assert!(path_records::<u64>(0..10).is_err());
+        assert!(delay_records::<u64>(0..10).is_err());
+    }
+
+    #[test]
+    fn can_find_path_records() {
+        let starts = path_records::<u64>(1..10_000)
+            .unwrap()
+            .iter()
+            .map(|stats| stats.start)
+            .collect::<Vec<u64>>();
+        assert_eq!(
+            starts,
+            vec![1, 2, 3, 7, 15, 27, 255, 447, 639, 703, 1819, 4255, 4591, 9663]
+        );
+
+        let peaks = path_records::<u64>(1..30)
+            .unwrap()
+            .iter()
+            .map(|stats| stats.peak)
+            .collect::<Vec<u64>>();
+        assert_eq!(peaks, vec![1, 2, 16, 52, 160, 9232]);
+    }
+
+    #[test]
+    fn can_find_delay_records() {
+        let starts = delay_records::<u64>(1..1000)
+            .unwrap()
+            .iter()
+            .map(|stats| stats.start)
+            .collect::<Vec<u64>>();
+        assert_eq!(
+            starts,
+            vec![1, 2, 3, 6, 7, 9, 18, 25, 27, 54, 73, 97, 129, 171, 231, 313, 327, 649, 703, 871]
+        );
+    }
+
+    #[test]
+    fn records_are_relative_to_the_range() {
+        let starts = delay_records::<u64>(100..200)
+            .unwrap()
+            .iter()
+            .map(|stats| stats.start)
+            .collect::<Vec<u64>>();
+        assert_eq!(starts[0], 100);
+        assert!(starts.contains(&129) && starts.contains(&171));
+    }
+}
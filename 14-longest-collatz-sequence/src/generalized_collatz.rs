@@ -0,0 +1,219 @@
+// Where a start ends up under a generalized map. `tail` counts the steps before the first
+// value on the cycle, and `index` points into `GeneralizedCollatz::known_cycles`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Orbit {
+    KnownCycle { index: usize, tail: u64 },
+    NewCycle { index: usize, tail: u64 },
+    Diverged { steps: u64 },
+}
+
+// n -> n / d when d divides n, and n -> a n + b otherwise, on signed values so negative starts
+// work. 3n + 1 is a = 3, b = 1, d = 2. A trajectory counts as diverging once a value passes
+// the bound in absolute value, or would overflow i128; inside the bound there are finitely
+// many values, so every other trajectory ends in a cycle.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct GeneralizedCollatz {
+    multiplier: i128,
+    increment: i128,
+    divisor: i128,
+    bound: i128,
+    known_cycles: Vec<Vec<i128>>,
+}
+
+impl GeneralizedCollatz {
+    pub fn new(multiplier: i128, increment: i128, divisor: i128, bound: i128) -> Self {
+        assert!(divisor >= 2, "Divisor must be at least 2");
+        assert!(bound > 0, "Bound must be positive");
+
+        GeneralizedCollatz {
+            multiplier,
+            increment,
+            divisor,
+            bound,
+            known_cycles: Vec::new(),
+        }
+    }
+
+    // 3n + 1, with 1 -> 4 -> 2 -> 1 already known.
+    pub fn classic(bound: i128) -> Self {
+        GeneralizedCollatz::new(3, 1, 2, bound).with_known_cycle(vec![1, 4, 2])
+    }
+
+    pub fn with_known_cycle(mut self, cycle: Vec<i128>) -> Self {
+        assert!(!cycle.is_empty(), "A cycle needs at least one value");
+        self.known_cycles.push(canonical_cycle(cycle));
+        self
+    }
+
+    // Each cycle is rotated to start at its smallest value.
+    pub fn known_cycles(&self) -> &[Vec<i128>] {
+        &self.known_cycles
+    }
+
+    // None if the next value would overflow i128.
+    pub fn step(&self, n: i128) -> Option<i128> {
+        if n.rem_euclid(self.divisor) == 0 {
+            Some(n / self.divisor)
+        } else {
+            n.checked_mul(self.multiplier)?.checked_add(self.increment)
+        }
+    }
+
+    fn bounded_step(&self, n: i128) -> Option<i128> {
+        self.step(n)
+            .filter(|next| next.unsigned_abs() <= self.bound.unsigned_abs())
+    }
+
+    // The start followed by every value after it, ending early only on overflow.
+    pub fn trajectory(&self, start: i128) -> impl Iterator<Item = i128> + '_ {
+        std::iter::successors(Some(start), move |&n| self.step(n))
+    }
+
+    // Brent's algorithm: the hare runs ahead while the tortoise waits at powers of two, which
+    // finds the cycle length in O(tail + cycle) steps without storing the trajectory. The
+    // tail then falls out of a second pass with the hare started one cycle length ahead.
+    pub fn classify(&mut self, start: i128) -> Orbit {
+        if start.unsigned_abs() > self.bound.unsigned_abs() {
+            return Orbit::Diverged { steps: 0 };
+        }
+
+        let (mut power, mut cycle_length) = (1u64, 1u64);
+        let mut steps = 1;
+        let mut tortoise = start;
+        let Some(mut hare) = self.bounded_step(start) else {
+            return Orbit::Diverged { steps };
+        };
+
+        while tortoise != hare {
+            if power == cycle_length {
+                tortoise = hare;
+                power *= 2;
+                cycle_length = 0;
+            }
+            steps += 1;
+            hare = match self.bounded_step(hare) {
+                Some(next) => next,
+                None => return Orbit::Diverged { steps },
+            };
+            cycle_length += 1;
+        }
+
+        // Every value from here on has already been seen inside the bound, so stepping
+        // cannot overflow.
+        let advance = |n: i128| self.step(n).expect("Cycle values stay inside the bound");
+        let mut hare = (0..cycle_length).fold(start, |n, _| advance(n));
+        let mut tortoise = start;
+        let mut tail = 0;
+        while tortoise != hare {
+            tortoise = advance(tortoise);
+            hare = advance(hare);
+            tail += 1;
+        }
+
+        let cycle = canonical_cycle(
+            self.trajectory(tortoise)
+                .take(cycle_length as usize)
+                .collect(),
+        );
+
+        match self.known_cycles.iter().position(|known| *known == cycle) {
+            Some(index) => Orbit::KnownCycle { index, tail },
+            None => {
+                self.known_cycles.push(cycle);
+                Orbit::NewCycle {
+                    index: self.known_cycles.len() - 1,
+                    tail,
+                }
+            }
+        }
+    }
+}
+
+fn canonical_cycle(mut cycle: Vec<i128>) -> Vec<i128> {
+    let smallest = (0..cycle.len())
+        .min_by_key(|&i| cycle[i])
+        .expect("A cycle needs at least one value");
+    cycle.rotate_left(smallest);
+    cycle
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classic_map_reaches_the_known_cycle() {
+        let mut map = GeneralizedCollatz::classic(1 << 40);
+        assert_eq!(map.classify(1), Orbit::KnownCycle { index: 0, tail: 0 });
+        assert_eq!(map.classify(8), Orbit::KnownCycle { index: 0, tail: 1 });
+        assert_eq!(
+            map.classify(27),
+            Orbit::KnownCycle {
+                index: 0,
+                tail: 109
+            }
+        );
+        assert_eq!(map.known_cycles(), &[vec![1, 4, 2]]);
+    }
+
+    #[test]
+    fn negative_starts_find_new_cycles() {
+        let mut map = GeneralizedCollatz::classic(1 << 40);
+        assert_eq!(map.classify(-1), Orbit::NewCycle { index: 1, tail: 0 });
+        assert_eq!(map.classify(-5), Orbit::NewCycle { index: 2, tail: 0 });
+        assert_eq!(map.classify(-40), Orbit::KnownCycle { index: 2, tail: 1 });
+        assert_eq!(map.classify(0), Orbit::NewCycle { index: 3, tail: 0 });
+
+        assert_eq!(map.known_cycles()[1], vec![-2, -1]);
+        assert_eq!(map.known_cycles()[2], vec![-20, -10, -5, -14, -7]);
+        assert_eq!(map.known_cycles()[3], vec![0]);
+
+        let cycles = (-1000..0).fold(map, |mut map, n| {
+            map.classify(n);
+            map
+        });
+        assert_eq!(cycles.known_cycles().len(), 5);
+        assert_eq!(cycles.known_cycles()[4].len(), 18);
+        assert_eq!(cycles.known_cycles()[4][0], -272);
+    }
+
+    #[test]
+    fn five_n_plus_one_has_several_cycles_and_diverges() {
+        let mut map = GeneralizedCollatz::new(5, 1, 2, 1_000_000_000_000);
+        assert_eq!(map.classify(1), Orbit::NewCycle { index: 0, tail: 0 });
+        assert_eq!(map.classify(13), Orbit::NewCycle { index: 1, tail: 0 });
+        assert_eq!(map.classify(17), Orbit::NewCycle { index: 2, tail: 0 });
+        assert_eq!(map.classify(26), Orbit::KnownCycle { index: 1, tail: 0 });
+        assert!(matches!(map.classify(7), Orbit::Diverged { .. }));
+
+        assert_eq!(map.known_cycles()[0], vec![1, 6, 3, 16, 8, 4, 2]);
+        assert_eq!(map.known_cycles()[1].len(), 10);
+        assert_eq!(map.known_cycles()[2].len(), 10);
+    }
+
+    #[test]
+    fn overflow_counts_as_divergence() {
+        let mut map = GeneralizedCollatz::new(5, 1, 2, i128::MAX);
+        let Orbit::Diverged { steps } = map.classify(7) else {
+            panic!("5n + 1 from 7 should overflow i128");
+        };
+        assert!(steps > 100);
+        assert_eq!(map.classify(i128::MAX), Orbit::Diverged { steps: 1 });
+    }
+
+    #[test]
+    fn other_divisors_work() {
+        // n / 3 on multiples of 3, n + 1 otherwise, so every start falls to 1 -> 2 -> 3 -> 1.
+        let mut map = GeneralizedCollatz::new(1, 1, 3, 1000);
+        assert_eq!(map.classify(1), Orbit::NewCycle { index: 0, tail: 0 });
+        assert_eq!(map.known_cycles()[0], vec![1, 2, 3]);
+        assert!((4..500).all(|n| matches!(map.classify(n), Orbit::KnownCycle { index: 0, .. })));
+    }
+
+    #[test]
+    fn trajectory_matches_the_classic_generator() {
+        let map = GeneralizedCollatz::classic(1 << 40);
+        let terms = map.trajectory(13).take(10).collect::<Vec<i128>>();
+        assert_eq!(terms, vec![13, 40, 20, 10, 5, 16, 8, 4, 2, 1]);
+    }
+}
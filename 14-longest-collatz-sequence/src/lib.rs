@@ -1 +1,4 @@
 pub mod collatz_generator;
+pub mod collatz_length;
+pub mod collatz_stats;
+pub mod generalized_collatz;
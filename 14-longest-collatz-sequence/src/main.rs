@@ -1,25 +1,16 @@
-use longest_collatz_sequence::collatz_generator::get_collatz_sequence_len;
-// use rayon::prelude::*;
-use std::sync::Mutex;
+use longest_collatz_sequence::collatz_length::CollatzLengthCache;
 
 fn main() {
-    let start = 1;
-    let end = 1_000_000;
-    let result = Mutex::new(0);
+    let limit = 1_000_000;
     println!(
-        "Calculating the longest Collatz sequence length from {} to {}",
-        start, end
+        "Calculating the longest Collatz sequence length below {}",
+        limit
     );
-    print!("0");
 
-    (start..=end)
-        // .into_par_iter()
-        .for_each(|n| {
-            let len = get_collatz_sequence_len(n);
-            let mut result = result.lock().unwrap();
-            if len > *result {
-                *result = len;
-                print!(" -> {} ({})", len, n);
-            }
-        });
+    let cache = CollatzLengthCache::new(limit as usize);
+    let (start, length) = cache
+        .longest_under(limit)
+        .expect("There is at least one start below the limit");
+
+    println!("{} has the longest sequence, with {} terms", start, length);
 }
@@ -1,37 +1,14 @@
-use lattice_paths::lattice_path::LatticePath;
+use euler_utils::combinatorics::binomial;
 
 fn main() {
-    // let n_sides = 20;
-    // calculate_complete_paths(n_sides);
-    // (2..10).for_each(calculate_complete_paths);
-    // (2..10).for_each(|x| {
-    //     println!(
-    //         "{} choose {} = {}",
-    //         2 * x,
-    //         x,
-    //         binomial_coefficient(2 * x, x)
-    //     )
-    // });
+    let n_sides = 20u64;
 
-    println!("40 choose 20 = {}", binomial_coefficient(40, 20));
-}
-
-fn calculate_complete_paths(n_sides: usize) {
-    let path = LatticePath::new(n_sides);
-    let n_complete_paths = path.n_complete_paths();
+    // A complete path is 2n moves, n of them down, so there are C(2n, n) of them.
+    let n_complete_paths =
+        binomial(2 * n_sides, n_sides).expect("C(40, 20) fits comfortably in a u64");
 
     println!(
         "Number of complete paths in a {}x{} lattice: {}",
         n_sides, n_sides, n_complete_paths
     );
 }
-
-fn binomial_coefficient(n: usize, k: usize) -> usize {
-    let mut result = 1;
-    for i in 0..k {
-        result *= n - i;
-        result /= i + 1;
-    }
-
-    result
-}
@@ -1,4 +1,7 @@
+use euler_utils::big_uint::BigUint;
+use euler_utils::digits::DecimalDigits;
+
 fn main() {
-    let x = 2u128.pow(1000);
-    println!("{}", x);
+    let x = BigUint::from(2u32).pow(1000);
+    println!("{}", x.digit_sum());
 }
@@ -0,0 +1,320 @@
+use crate::big_uint::{forward_binary_op, BigUint};
+use crate::integer::{Semiring, SignedInt};
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{
+    Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Sub, SubAssign,
+};
+use std::str::FromStr;
+
+// Sign and magnitude. Zero is never negative, so the derived equality and hash are numeric.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Default)]
+pub struct BigInt {
+    negative: bool,
+    magnitude: BigUint,
+}
+
+impl BigInt {
+    pub fn new() -> Self {
+        BigInt {
+            negative: false,
+            magnitude: BigUint::zero(),
+        }
+    }
+
+    pub fn from_parts(negative: bool, magnitude: BigUint) -> Self {
+        BigInt {
+            negative: negative && !magnitude.is_zero(),
+            magnitude,
+        }
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.magnitude.is_zero()
+    }
+
+    pub fn magnitude(&self) -> &BigUint {
+        &self.magnitude
+    }
+
+    pub fn into_magnitude(self) -> BigUint {
+        self.magnitude
+    }
+
+    pub fn signum(&self) -> i8 {
+        match (self.negative, self.is_zero()) {
+            (_, true) => 0,
+            (true, _) => -1,
+            (false, _) => 1,
+        }
+    }
+
+    pub fn pow(&self, exponent: u32) -> BigInt {
+        BigInt::from_parts(
+            self.negative && exponent % 2 == 1,
+            self.magnitude.pow(exponent),
+        )
+    }
+
+    pub fn to_i128(&self) -> Option<i128> {
+        let magnitude = self.magnitude.to_u128()?;
+        if self.negative {
+            0i128.checked_sub_unsigned(magnitude)
+        } else {
+            i128::try_from(magnitude).ok()
+        }
+    }
+}
+
+impl SignedInt for BigInt {
+    fn zero() -> Self {
+        BigInt::new()
+    }
+
+    fn one() -> Self {
+        BigInt::from(1)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.magnitude.is_zero()
+    }
+
+    fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    fn abs(&self) -> Self {
+        BigInt::from(self.magnitude.clone())
+    }
+}
+
+impl Semiring for BigInt {
+    fn zero_like(&self) -> Self {
+        BigInt::zero()
+    }
+
+    fn one_like(&self) -> Self {
+        BigInt::one()
+    }
+}
+
+impl From<BigUint> for BigInt {
+    fn from(magnitude: BigUint) -> Self {
+        BigInt::from_parts(false, magnitude)
+    }
+}
+
+macro_rules! impl_from_signed {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for BigInt {
+                fn from(n: $t) -> Self {
+                    BigInt::from_parts(n < 0, BigUint::from(n.unsigned_abs()))
+                }
+            }
+        )*
+    };
+}
+
+impl_from_signed!(i32, i64, i128);
+
+impl FromStr for BigInt {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+
+        digits
+            .parse::<BigUint>()
+            .map(|magnitude| BigInt::from_parts(negative, magnitude))
+            .map_err(|_| format!("Invalid integer: {:?}", s))
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad_integral(!self.negative, "", &self.magnitude.to_string())
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, false) => self.magnitude.cmp(&other.magnitude),
+            (true, true) => other.magnitude.cmp(&self.magnitude),
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Neg for BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::from_parts(!self.negative, self.magnitude)
+    }
+}
+
+impl Neg for &BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        -self.clone()
+    }
+}
+
+impl Add<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn add(self, other: &BigInt) -> BigInt {
+        if self.negative == other.negative {
+            return BigInt::from_parts(self.negative, &self.magnitude + &other.magnitude);
+        }
+
+        match self.magnitude.cmp(&other.magnitude) {
+            Ordering::Less => {
+                BigInt::from_parts(other.negative, &other.magnitude - &self.magnitude)
+            }
+            _ => BigInt::from_parts(self.negative, &self.magnitude - &other.magnitude),
+        }
+    }
+}
+
+impl Sub<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn sub(self, other: &BigInt) -> BigInt {
+        self + &(-other)
+    }
+}
+
+impl Mul<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn mul(self, other: &BigInt) -> BigInt {
+        BigInt::from_parts(
+            self.negative != other.negative,
+            &self.magnitude * &other.magnitude,
+        )
+    }
+}
+
+// Division truncates towards zero and the remainder takes the dividend's sign, as for i64.
+impl Div<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn div(self, other: &BigInt) -> BigInt {
+        BigInt::from_parts(
+            self.negative != other.negative,
+            &self.magnitude / &other.magnitude,
+        )
+    }
+}
+
+impl Rem<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn rem(self, other: &BigInt) -> BigInt {
+        BigInt::from_parts(self.negative, &self.magnitude % &other.magnitude)
+    }
+}
+
+forward_binary_op!(
+    BigInt;
+    Add, add, AddAssign, add_assign;
+    Sub, sub, SubAssign, sub_assign;
+    Mul, mul, MulAssign, mul_assign;
+    Div, div, DivAssign, div_assign;
+    Rem, rem, RemAssign, rem_assign
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn big(s: &str) -> BigInt {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn can_parse_and_display() {
+        ["0", "-1", "42", "-123456789012345678901234567890"]
+            .iter()
+            .for_each(|&s| assert_eq!(big(s).to_string(), s));
+
+        assert_eq!(big("+17"), BigInt::from(17));
+        assert_eq!(big("-0"), BigInt::zero());
+        assert_eq!(format!("{:+}", big("5")), "+5");
+        assert!(BigInt::from_str("--1").is_err());
+        assert!(BigInt::from_str("-").is_err());
+    }
+
+    #[test]
+    fn arithmetic_matches_i128() {
+        let values = [
+            0i128,
+            1,
+            -1,
+            7,
+            -7,
+            999_999_999,
+            -1_000_000_000,
+            123_456_789_012_345_678,
+            -987_654_321_098_765_432,
+        ];
+
+        values.iter().for_each(|&a| {
+            values.iter().for_each(|&b| {
+                let (x, y) = (BigInt::from(a), BigInt::from(b));
+                assert_eq!(&x + &y, BigInt::from(a + b), "{} + {}", a, b);
+                assert_eq!(&x - &y, BigInt::from(a - b), "{} - {}", a, b);
+                assert_eq!(&x * &y, BigInt::from(a * b), "{} * {}", a, b);
+                assert_eq!(x.cmp(&y), a.cmp(&b));
+                if b != 0 {
+                    assert_eq!(&x / &y, BigInt::from(a / b), "{} / {}", a, b);
+                    assert_eq!(&x % &y, BigInt::from(a % b), "{} % {}", a, b);
+                    assert_eq!(x.div_floor(&y), BigInt::from(SignedInt::div_floor(&a, &b)));
+                }
+            });
+        });
+    }
+
+    #[test]
+    fn can_negate_and_take_signs() {
+        let n = big("-12345678901234567890");
+        assert_eq!(-&n, big("12345678901234567890"));
+        assert_eq!(n.signum(), -1);
+        assert_eq!(n.abs().signum(), 1);
+        assert_eq!(BigInt::zero().signum(), 0);
+        assert_eq!(-BigInt::zero(), BigInt::zero());
+    }
+
+    #[test]
+    fn can_raise_negative_numbers_to_powers() {
+        assert_eq!(BigInt::from(-2).pow(3), BigInt::from(-8));
+        assert_eq!(BigInt::from(-2).pow(64), big("18446744073709551616"));
+        assert_eq!(BigInt::from(-2).pow(127).to_i128(), Some(i128::MIN));
+        assert_eq!(BigInt::from(2).pow(127).to_i128(), None);
+    }
+
+    #[test]
+    fn gcd_works_on_big_values() {
+        let a = big("-1234567890123456789012345678900");
+        let b = big("9876543210987654321098765432100");
+        assert_eq!(a.gcd(&b), big("90000000009000000000900"));
+    }
+}
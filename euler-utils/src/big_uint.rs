@@ -0,0 +1,1108 @@
+use crate::integer::Semiring;
+use crate::segmented_sieve::SegmentedSieve;
+use std::cmp::Ordering;
+use std::fmt;
+use std::iter::{Product, Sum};
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Rem, RemAssign, Sub, SubAssign};
+use std::str::FromStr;
+
+const BASE: u64 = 1_000_000_000;
+const BASE_DIGITS: usize = 9;
+
+// Below this many limbs in the shorter operand, Karatsuba's extra additions cost more than
+// the multiplications they save.
+const KARATSUBA_THRESHOLD: usize = 32;
+
+// Little-endian limbs in base 10^9 with no trailing zero limbs, so zero is the empty vector
+// and the derived equality and hash agree with numeric equality.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Default)]
+pub struct BigUint {
+    limbs: Vec<u32>,
+}
+
+impl BigUint {
+    pub fn new() -> Self {
+        BigUint { limbs: Vec::new() }
+    }
+
+    pub fn zero() -> Self {
+        BigUint::new()
+    }
+
+    pub fn one() -> Self {
+        BigUint::from(1u32)
+    }
+
+    fn from_limbs(limbs: Vec<u32>) -> Self {
+        let mut n = BigUint { limbs };
+        n.normalize();
+        n
+    }
+
+    fn normalize(&mut self) {
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    pub fn digit_count(&self) -> usize {
+        match self.limbs.last() {
+            Some(top) => (self.limbs.len() - 1) * BASE_DIGITS + top.ilog10() as usize + 1,
+            None => 1,
+        }
+    }
+
+    // Decimal digits, least significant first; `.rev()` gives them most significant first.
+    pub fn digits(&self) -> BigUintDigits<'_> {
+        BigUintDigits {
+            limbs: &self.limbs,
+            front: 0,
+            back: self.digit_count(),
+        }
+    }
+
+    pub fn reverse_digits(&self) -> BigUint {
+        self.digits()
+            .map(|d| char::from(b'0' + d as u8))
+            .collect::<String>()
+            .parse()
+            .expect("Digits always parse")
+    }
+
+    pub fn concatenate(&self, other: &BigUint) -> BigUint {
+        self * &BigUint::from(10u32).pow(other.digit_count() as u32) + other
+    }
+
+    pub fn to_u64(&self) -> Option<u64> {
+        u64::try_from(self.to_u128()?).ok()
+    }
+
+    pub fn to_u128(&self) -> Option<u128> {
+        self.limbs.iter().rev().try_fold(0u128, |acc, &limb| {
+            acc.checked_mul(BASE as u128)?.checked_add(limb as u128)
+        })
+    }
+
+    pub fn checked_sub(&self, other: &BigUint) -> Option<BigUint> {
+        if *self < *other {
+            return None;
+        }
+
+        let mut limbs = self.limbs.clone();
+        let mut borrow = 0i64;
+        for (i, limb) in limbs.iter_mut().enumerate() {
+            let subtrahend = other.limbs.get(i).copied().unwrap_or(0) as i64 + borrow;
+            let mut difference = *limb as i64 - subtrahend;
+            borrow = 0;
+            if difference < 0 {
+                difference += BASE as i64;
+                borrow = 1;
+            }
+            *limb = difference as u32;
+        }
+
+        Some(BigUint::from_limbs(limbs))
+    }
+
+    pub fn mul_small(&self, factor: u32) -> BigUint {
+        let mut limbs = Vec::with_capacity(self.limbs.len() + 1);
+        let mut carry = 0u64;
+        for &limb in &self.limbs {
+            let product = limb as u64 * factor as u64 + carry;
+            limbs.push((product % BASE) as u32);
+            carry = product / BASE;
+        }
+        while carry > 0 {
+            limbs.push((carry % BASE) as u32);
+            carry /= BASE;
+        }
+        BigUint::from_limbs(limbs)
+    }
+
+    pub fn div_rem_small(&self, divisor: u32) -> (BigUint, u32) {
+        assert!(divisor != 0, "Attempt to divide by zero");
+
+        let mut quotient = vec![0; self.limbs.len()];
+        let mut remainder = 0u64;
+        for i in (0..self.limbs.len()).rev() {
+            let current = remainder * BASE + self.limbs[i] as u64;
+            quotient[i] = (current / divisor as u64) as u32;
+            remainder = current % divisor as u64;
+        }
+
+        (BigUint::from_limbs(quotient), remainder as u32)
+    }
+
+    // Schoolbook long division, one base 10^9 digit of the quotient at a time. Estimating each
+    // digit from the top three limbs of the remainder over the top two of the divisor is off by
+    // at most two, which the correction loops fix.
+    pub fn div_rem(&self, divisor: &BigUint) -> (BigUint, BigUint) {
+        assert!(!divisor.is_zero(), "Attempt to divide by zero");
+
+        if *self < *divisor {
+            return (BigUint::zero(), self.clone());
+        }
+        if divisor.limbs.len() == 1 {
+            let (quotient, remainder) = self.div_rem_small(divisor.limbs[0]);
+            return (quotient, BigUint::from(remainder));
+        }
+
+        let n = divisor.limbs.len();
+        let divisor_top =
+            divisor.limbs[n - 1] as u128 * BASE as u128 + divisor.limbs[n - 2] as u128;
+        let mut quotient = vec![0; self.limbs.len()];
+        let mut remainder = BigUint::zero();
+
+        for i in (0..self.limbs.len()).rev() {
+            remainder.limbs.insert(0, self.limbs[i]);
+            remainder.normalize();
+            if remainder < *divisor {
+                continue;
+            }
+
+            let limb = |k: usize| remainder.limbs.get(k).copied().unwrap_or(0) as u128;
+            let remainder_top = (limb(n) * BASE as u128 + limb(n - 1)) * BASE as u128 + limb(n - 2);
+            let mut q = (remainder_top / divisor_top).min(BASE as u128 - 1) as u32;
+
+            let mut product = divisor.mul_small(q);
+            while product > remainder {
+                q -= 1;
+                product -= divisor;
+            }
+            remainder -= &product;
+            while remainder >= *divisor {
+                q += 1;
+                remainder -= divisor;
+            }
+
+            quotient[i] = q;
+        }
+
+        (BigUint::from_limbs(quotient), remainder)
+    }
+
+    pub fn square(&self) -> BigUint {
+        BigUint::from_limbs(square_limbs(&self.limbs))
+    }
+
+    pub fn pow(&self, exponent: u32) -> BigUint {
+        let mut result = BigUint::one();
+        let mut base = self.clone();
+        let mut exponent = exponent;
+
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = &result * &base;
+            }
+            exponent >>= 1;
+            if exponent > 0 {
+                base = base.square();
+            }
+        }
+
+        result
+    }
+
+    // Multiplies neighbours pairwise until one value is left, so the big multiplications
+    // happen between operands of similar size where Karatsuba pays off.
+    pub fn product_tree(values: Vec<BigUint>) -> BigUint {
+        let mut values = values;
+        while values.len() > 1 {
+            values = values
+                .chunks(2)
+                .map(|pair| match pair {
+                    [a, b] => a * b,
+                    [a] => a.clone(),
+                    _ => unreachable!(),
+                })
+                .collect();
+        }
+        values.pop().unwrap_or_else(BigUint::one)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BigUintDigits<'a> {
+    limbs: &'a [u32],
+    front: usize,
+    back: usize,
+}
+
+impl BigUintDigits<'_> {
+    fn digit(&self, i: usize) -> u32 {
+        let limb = self.limbs.get(i / BASE_DIGITS).copied().unwrap_or(0);
+        limb / 10u32.pow((i % BASE_DIGITS) as u32) % 10
+    }
+}
+
+impl Iterator for BigUintDigits<'_> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+        self.front += 1;
+        Some(self.digit(self.front - 1))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+}
+
+impl DoubleEndedIterator for BigUintDigits<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(self.digit(self.back))
+    }
+}
+
+impl ExactSizeIterator for BigUintDigits<'_> {}
+
+// The exponent of the prime p in n!, n/p + n/p^2 + ... .
+pub(crate) fn legendre_exponent(n: u64, p: u64) -> u64 {
+    let mut exponent = 0;
+    let mut power = p;
+    loop {
+        exponent += n / power;
+        match power.checked_mul(p) {
+            Some(next) if next <= n => power = next,
+            _ => break,
+        }
+    }
+    exponent
+}
+
+// The product of p^e over (p, e). Each p^e splits into (p^k)^q, raised by squaring, times a
+// p^r that fits in a u64, where p^k is the largest power of p that does. The p^r are packed
+// into u64s before going into the tree so that most of the multiplications are cheap.
+pub(crate) fn product_of_prime_powers(factors: impl IntoIterator<Item = (u64, u64)>) -> BigUint {
+    let mut packed = Vec::new();
+    let mut current = 1u64;
+
+    for (p, exponent) in factors {
+        let (mut word, mut k) = (p, 1);
+        while let Some(next) = word.checked_mul(p) {
+            word = next;
+            k += 1;
+        }
+
+        let (q, r) = (exponent / k, exponent % k);
+        if q > 0 {
+            let q = u32::try_from(q).expect("Prime exponent too large to raise by squaring");
+            packed.push(BigUint::from(word).pow(q));
+        }
+
+        let rest = p.pow(r as u32);
+        match current.checked_mul(rest) {
+            Some(next) => current = next,
+            None => {
+                packed.push(BigUint::from(current));
+                current = rest;
+            }
+        }
+    }
+    packed.push(BigUint::from(current));
+
+    BigUint::product_tree(packed)
+}
+
+// n! as the product of p^e over primes p <= n, where Legendre's formula gives e.
+pub fn factorial(n: u64) -> BigUint {
+    product_of_prime_powers(
+        SegmentedSieve::new(n as usize + 1)
+            .primes()
+            .into_iter()
+            .map(|p| (p as u64, legendre_exponent(n, p as u64))),
+    )
+}
+
+fn trim(limbs: &[u32]) -> &[u32] {
+    let len = limbs
+        .iter()
+        .rposition(|&limb| limb != 0)
+        .map_or(0, |i| i + 1);
+    &limbs[..len]
+}
+
+fn add_limbs(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut sum = a.to_vec();
+    add_shifted(&mut sum, b, 0);
+    sum
+}
+
+// a -= b, where the caller guarantees a >= b.
+fn sub_limbs_in_place(a: &mut [u32], b: &[u32]) {
+    let mut borrow = 0u32;
+    for (i, limb) in a.iter_mut().enumerate() {
+        if i >= b.len() && borrow == 0 {
+            break;
+        }
+        let subtrahend = b.get(i).copied().unwrap_or(0) + borrow;
+        (*limb, borrow) = if *limb >= subtrahend {
+            (*limb - subtrahend, 0)
+        } else {
+            (*limb + BASE as u32 - subtrahend, 1)
+        };
+    }
+    debug_assert_eq!(borrow, 0);
+}
+
+// target += x * BASE^shift, growing target as needed.
+fn add_shifted(target: &mut Vec<u32>, x: &[u32], shift: usize) {
+    if target.len() < shift + x.len() {
+        target.resize(shift + x.len(), 0);
+    }
+
+    let mut carry = 0u32;
+    let mut i = shift;
+    for &limb in x {
+        let sum = target[i] + limb + carry;
+        (target[i], carry) = if sum >= BASE as u32 {
+            (sum - BASE as u32, 1)
+        } else {
+            (sum, 0)
+        };
+        i += 1;
+    }
+    while carry > 0 {
+        if i == target.len() {
+            target.push(0);
+        }
+        let sum = target[i] + carry;
+        (target[i], carry) = if sum >= BASE as u32 {
+            (sum - BASE as u32, 1)
+        } else {
+            (sum, 0)
+        };
+        i += 1;
+    }
+}
+
+fn schoolbook_multiply(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut accumulator = vec![0u64; a.len() + b.len()];
+    for (i, &x) in a.iter().enumerate() {
+        let mut carry = 0u64;
+        for (j, &y) in b.iter().enumerate() {
+            let current = accumulator[i + j] + x as u64 * y as u64 + carry;
+            accumulator[i + j] = current % BASE;
+            carry = current / BASE;
+        }
+        accumulator[i + b.len()] += carry;
+    }
+
+    accumulator.into_iter().map(|limb| limb as u32).collect()
+}
+
+// Each cross term a_i a_j with i < j is formed once, then the whole sum is doubled and the
+// squares a_i^2 added on the diagonal, so about half the multiplications of a general product.
+fn schoolbook_square(a: &[u32]) -> Vec<u32> {
+    let n = a.len();
+    let mut accumulator = vec![0u64; 2 * n];
+    for (i, &x) in a.iter().enumerate() {
+        let mut carry = 0u64;
+        for (j, &y) in a.iter().enumerate().skip(i + 1) {
+            let current = accumulator[i + j] + x as u64 * y as u64 + carry;
+            accumulator[i + j] = current % BASE;
+            carry = current / BASE;
+        }
+        accumulator[i + n] += carry;
+    }
+
+    let mut carry = 0u64;
+    for (k, limb) in accumulator.iter_mut().enumerate() {
+        let diagonal = if k % 2 == 0 {
+            a[k / 2] as u64 * a[k / 2] as u64
+        } else {
+            0
+        };
+        let current = 2 * *limb + diagonal + carry;
+        *limb = current % BASE;
+        carry = current / BASE;
+    }
+    debug_assert_eq!(carry, 0);
+
+    accumulator.into_iter().map(|limb| limb as u32).collect()
+}
+
+// Karatsuba for a square: a^2 = a1^2 B^2m + ((a0 + a1)^2 - a0^2 - a1^2) B^m + a0^2, where all
+// three half-size products are squares again.
+fn square_limbs(a: &[u32]) -> Vec<u32> {
+    let a = trim(a);
+    if a.is_empty() {
+        return Vec::new();
+    }
+    if a.len() < KARATSUBA_THRESHOLD {
+        return schoolbook_square(a);
+    }
+
+    let m = a.len() / 2;
+    let (low_half, high_half) = a.split_at(m);
+
+    let low = square_limbs(low_half);
+    let high = square_limbs(high_half);
+    let mut middle = square_limbs(&add_limbs(low_half, high_half));
+    sub_limbs_in_place(&mut middle, trim(&low));
+    sub_limbs_in_place(&mut middle, trim(&high));
+
+    let mut square = low;
+    add_shifted(&mut square, trim(&middle), m);
+    add_shifted(&mut square, trim(&high), 2 * m);
+    square
+}
+
+// Karatsuba: with a = a1 B^m + a0 and b = b1 B^m + b0,
+// ab = a1 b1 B^2m + ((a0 + a1)(b0 + b1) - a0 b0 - a1 b1) B^m + a0 b0, three half-size products.
+// Lopsided operands are cut into pieces the size of the shorter one first.
+fn multiply_limbs(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let (a, b) = (trim(a), trim(b));
+    let (long, short) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+
+    if short.is_empty() {
+        return Vec::new();
+    }
+    if short.len() < KARATSUBA_THRESHOLD {
+        return schoolbook_multiply(long, short);
+    }
+    if long.len() >= 2 * short.len() {
+        let mut product = Vec::new();
+        for (i, piece) in long.chunks(short.len()).enumerate() {
+            add_shifted(&mut product, &multiply_limbs(piece, short), i * short.len());
+        }
+        return product;
+    }
+
+    let m = long.len() / 2;
+    let (long_low, long_high) = long.split_at(m);
+    let (short_low, short_high) = short.split_at(m);
+
+    let low = multiply_limbs(long_low, short_low);
+    let high = multiply_limbs(long_high, short_high);
+    let mut middle = multiply_limbs(
+        &add_limbs(long_low, long_high),
+        &add_limbs(short_low, short_high),
+    );
+    sub_limbs_in_place(&mut middle, trim(&low));
+    sub_limbs_in_place(&mut middle, trim(&high));
+
+    let mut product = low;
+    add_shifted(&mut product, trim(&middle), m);
+    add_shifted(&mut product, trim(&high), 2 * m);
+    product
+}
+
+impl From<u8> for BigUint {
+    fn from(n: u8) -> Self {
+        BigUint::from(n as u128)
+    }
+}
+
+impl From<u32> for BigUint {
+    fn from(n: u32) -> Self {
+        BigUint::from(n as u128)
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(n: u64) -> Self {
+        BigUint::from(n as u128)
+    }
+}
+
+impl From<usize> for BigUint {
+    fn from(n: usize) -> Self {
+        BigUint::from(n as u128)
+    }
+}
+
+impl From<u128> for BigUint {
+    fn from(n: u128) -> Self {
+        let mut limbs = Vec::new();
+        let mut n = n;
+        while n > 0 {
+            limbs.push((n % BASE as u128) as u32);
+            n /= BASE as u128;
+        }
+        BigUint { limbs }
+    }
+}
+
+impl FromStr for BigUint {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(format!("Invalid unsigned integer: {:?}", s));
+        }
+
+        let limbs = s
+            .as_bytes()
+            .rchunks(BASE_DIGITS)
+            .map(|chunk| chunk.iter().fold(0, |acc, &b| acc * 10 + (b - b'0') as u32))
+            .collect();
+
+        Ok(BigUint::from_limbs(limbs))
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut s = match self.limbs.last() {
+            Some(top) => top.to_string(),
+            None => "0".to_string(),
+        };
+        self.limbs
+            .iter()
+            .rev()
+            .skip(1)
+            .for_each(|limb| s.push_str(&format!("{:09}", limb)));
+
+        f.pad_integral(true, "", &s)
+    }
+}
+
+impl Semiring for BigUint {
+    fn zero_like(&self) -> Self {
+        BigUint::zero()
+    }
+
+    fn one_like(&self) -> Self {
+        BigUint::one()
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.limbs
+            .len()
+            .cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Add<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn add(self, other: &BigUint) -> BigUint {
+        let (long, short) = if self.limbs.len() >= other.limbs.len() {
+            (self, other)
+        } else {
+            (other, self)
+        };
+
+        let mut limbs = Vec::with_capacity(long.limbs.len() + 1);
+        let mut carry = 0u32;
+        for (i, &limb) in long.limbs.iter().enumerate() {
+            let mut sum = limb + short.limbs.get(i).copied().unwrap_or(0) + carry;
+            carry = 0;
+            if sum >= BASE as u32 {
+                sum -= BASE as u32;
+                carry = 1;
+            }
+            limbs.push(sum);
+        }
+        if carry > 0 {
+            limbs.push(carry);
+        }
+
+        BigUint { limbs }
+    }
+}
+
+impl Sub<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn sub(self, other: &BigUint) -> BigUint {
+        self.checked_sub(other)
+            .expect("Attempt to subtract with overflow")
+    }
+}
+
+impl Mul<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn mul(self, other: &BigUint) -> BigUint {
+        BigUint::from_limbs(multiply_limbs(&self.limbs, &other.limbs))
+    }
+}
+
+impl Div<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn div(self, other: &BigUint) -> BigUint {
+        self.div_rem(other).0
+    }
+}
+
+impl Rem<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn rem(self, other: &BigUint) -> BigUint {
+        self.div_rem(other).1
+    }
+}
+
+// Forwards the owned and mixed forms of each operator, and its assigning form, to the
+// type's own `&T op &T` impl.
+macro_rules! forward_binary_op {
+    ($t:ty; $($trait:ident, $method:ident, $assign_trait:ident, $assign_method:ident);*) => {
+        $(
+            impl $trait<$t> for $t {
+                type Output = $t;
+
+                fn $method(self, other: $t) -> $t {
+                    (&self).$method(&other)
+                }
+            }
+
+            impl $trait<&$t> for $t {
+                type Output = $t;
+
+                fn $method(self, other: &$t) -> $t {
+                    (&self).$method(other)
+                }
+            }
+
+            impl $trait<$t> for &$t {
+                type Output = $t;
+
+                fn $method(self, other: $t) -> $t {
+                    self.$method(&other)
+                }
+            }
+
+            impl $assign_trait<$t> for $t {
+                fn $assign_method(&mut self, other: $t) {
+                    *self = (&*self).$method(&other);
+                }
+            }
+
+            impl $assign_trait<&$t> for $t {
+                fn $assign_method(&mut self, other: &$t) {
+                    *self = (&*self).$method(other);
+                }
+            }
+        )*
+    };
+}
+
+pub(crate) use forward_binary_op;
+
+forward_binary_op!(
+    BigUint;
+    Add, add, AddAssign, add_assign;
+    Sub, sub, SubAssign, sub_assign;
+    Mul, mul, MulAssign, mul_assign;
+    Div, div, DivAssign, div_assign;
+    Rem, rem, RemAssign, rem_assign
+);
+
+impl Mul<u32> for &BigUint {
+    type Output = BigUint;
+
+    fn mul(self, other: u32) -> BigUint {
+        self.mul_small(other)
+    }
+}
+
+impl Mul<u32> for BigUint {
+    type Output = BigUint;
+
+    fn mul(self, other: u32) -> BigUint {
+        self.mul_small(other)
+    }
+}
+
+impl MulAssign<u32> for BigUint {
+    fn mul_assign(&mut self, other: u32) {
+        *self = self.mul_small(other);
+    }
+}
+
+impl Div<u32> for &BigUint {
+    type Output = BigUint;
+
+    fn div(self, other: u32) -> BigUint {
+        self.div_rem_small(other).0
+    }
+}
+
+impl Div<u32> for BigUint {
+    type Output = BigUint;
+
+    fn div(self, other: u32) -> BigUint {
+        self.div_rem_small(other).0
+    }
+}
+
+impl DivAssign<u32> for BigUint {
+    fn div_assign(&mut self, other: u32) {
+        *self = self.div_rem_small(other).0;
+    }
+}
+
+impl Rem<u32> for &BigUint {
+    type Output = u32;
+
+    fn rem(self, other: u32) -> u32 {
+        self.div_rem_small(other).1
+    }
+}
+
+impl Rem<u32> for BigUint {
+    type Output = u32;
+
+    fn rem(self, other: u32) -> u32 {
+        self.div_rem_small(other).1
+    }
+}
+
+impl Sum for BigUint {
+    fn sum<I: Iterator<Item = BigUint>>(iter: I) -> Self {
+        iter.fold(BigUint::zero(), |acc, n| acc + n)
+    }
+}
+
+impl<'a> Sum<&'a BigUint> for BigUint {
+    fn sum<I: Iterator<Item = &'a BigUint>>(iter: I) -> Self {
+        iter.fold(BigUint::zero(), |acc, n| acc + n)
+    }
+}
+
+impl Product for BigUint {
+    fn product<I: Iterator<Item = BigUint>>(iter: I) -> Self {
+        iter.fold(BigUint::one(), |acc, n| acc * n)
+    }
+}
+
+impl<'a> Product<&'a BigUint> for BigUint {
+    fn product<I: Iterator<Item = &'a BigUint>>(iter: I) -> Self {
+        iter.fold(BigUint::one(), |acc, n| acc * n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn big(s: &str) -> BigUint {
+        s.parse().unwrap()
+    }
+
+    // A cheap deterministic stream of u128s for checking against native arithmetic.
+    fn samples() -> Vec<u128> {
+        let mut x = 0x9E37_79B9_7F4A_7C15u128;
+        (0..200)
+            .map(|i| {
+                x = x
+                    .wrapping_mul(6_364_136_223_846_793_005)
+                    .wrapping_add(1_442_695_040_888_963_407);
+                x >> (i % 128)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn can_parse_and_display() {
+        [
+            "0",
+            "7",
+            "999999999",
+            "1000000000",
+            "123456789012345678901234567890",
+        ]
+        .iter()
+        .for_each(|&s| assert_eq!(big(s).to_string(), s));
+
+        assert_eq!(big("000120").to_string(), "120");
+        assert_eq!(format!("{:>6}", big("42")), "    42");
+        assert!(BigUint::from_str("").is_err());
+        assert!(BigUint::from_str("12a").is_err());
+        assert!(BigUint::from_str("-1").is_err());
+    }
+
+    #[test]
+    fn zero_is_normalized() {
+        assert_eq!(big("0000"), BigUint::zero());
+        assert_eq!(big("1000000000") - big("1000000000"), BigUint::default());
+        assert!(BigUint::from(0u64).is_zero());
+    }
+
+    #[test]
+    fn arithmetic_matches_u128() {
+        let xs = samples();
+        xs.iter().zip(xs.iter().skip(1)).for_each(|(&a, &b)| {
+            let (a, b) = (a >> 1, b >> 1);
+            assert_eq!(BigUint::from(a) + BigUint::from(b), BigUint::from(a + b));
+
+            let (hi, lo) = (a.max(b), a.min(b));
+            assert_eq!(
+                BigUint::from(hi) - BigUint::from(lo),
+                BigUint::from(hi - lo)
+            );
+
+            let (x, y) = (a >> 64, b >> 64);
+            assert_eq!(BigUint::from(x) * BigUint::from(y), BigUint::from(x * y));
+
+            if lo > 0 {
+                let (q, r) = BigUint::from(hi).div_rem(&BigUint::from(lo));
+                assert_eq!((q, r), (BigUint::from(hi / lo), BigUint::from(hi % lo)));
+            }
+            assert_eq!(a.cmp(&b), BigUint::from(a).cmp(&BigUint::from(b)));
+        });
+    }
+
+    #[test]
+    fn division_reconstructs_the_dividend() {
+        let a = big("98765432109876543210987654321098765432109876543210987654321");
+        let divisors = [
+            "1",
+            "3",
+            "999999999",
+            "1000000000",
+            "1000000001",
+            "123456789123456789",
+            "99999999999999999999999999",
+            "98765432109876543210987654321098765432109876543210987654322",
+        ];
+
+        divisors.iter().for_each(|&d| {
+            let d = big(d);
+            let (q, r) = a.div_rem(&d);
+            assert!(r < d);
+            assert_eq!(&q * &d + &r, a, "divisor {}", d);
+        });
+    }
+
+    #[test]
+    fn division_undoes_multiplication() {
+        let xs = samples()
+            .into_iter()
+            .map(BigUint::from)
+            .collect::<Vec<BigUint>>();
+        xs.windows(3).filter(|w| !w[1].is_zero()).for_each(|w| {
+            let a = &w[0] * &w[2] + BigUint::one();
+            let b = &w[1] * &w[1];
+            let r = &a % &b;
+            assert_eq!((&a * &b + &r).div_rem(&b), (a, r));
+        });
+    }
+
+    #[test]
+    fn can_divide_by_small_values() {
+        let a = big("100000000000000000000000000000");
+        assert_eq!(&a / 7u32, big("14285714285714285714285714285"));
+        assert_eq!(&a % 7u32, 5);
+        assert_eq!(a.mul_small(3), big("300000000000000000000000000000"));
+    }
+
+    #[test]
+    #[should_panic(expected = "overflow")]
+    fn subtracting_a_larger_number_panics() {
+        let _ = big("5") - big("1000000000000");
+    }
+
+    #[test]
+    #[should_panic(expected = "divide by zero")]
+    fn dividing_by_zero_panics() {
+        let _ = big("5") / BigUint::zero();
+    }
+
+    #[test]
+    fn checked_sub_reports_underflow() {
+        assert_eq!(big("5").checked_sub(&big("6")), None);
+        assert_eq!(
+            big("1000000000").checked_sub(&big("1")),
+            Some(big("999999999"))
+        );
+    }
+
+    #[test]
+    fn can_raise_to_powers() {
+        assert_eq!(
+            BigUint::from(2u32).pow(128).to_string(),
+            "340282366920938463463374607431768211456"
+        );
+        assert_eq!(BigUint::from(2u32).pow(64).to_u64(), None);
+        assert_eq!(BigUint::from(2u32).pow(63).to_u64(), Some(1 << 63));
+        assert_eq!(BigUint::zero().pow(0), BigUint::one());
+    }
+
+    fn digit_sum(n: &BigUint) -> u64 {
+        n.to_string().bytes().map(|b| (b - b'0') as u64).sum()
+    }
+
+    #[test]
+    fn karatsuba_matches_schoolbook() {
+        let xs = samples();
+        let limbs = |offset: usize, len: usize| {
+            (0..len)
+                .map(|i| (xs[(offset + i) % xs.len()] % BASE as u128) as u32)
+                .collect::<Vec<u32>>()
+        };
+
+        [
+            (32, 32),
+            (33, 100),
+            (64, 65),
+            (200, 31),
+            (257, 300),
+            (1000, 999),
+        ]
+        .iter()
+        .for_each(|&(m, n)| {
+            let (a, b) = (limbs(m, m), limbs(n + 7, n));
+            let expected = BigUint::from_limbs(schoolbook_multiply(&a, &b));
+            assert_eq!(
+                BigUint::from_limbs(multiply_limbs(&a, &b)),
+                expected,
+                "{} x {}",
+                m,
+                n
+            );
+        });
+    }
+
+    #[test]
+    fn square_matches_multiplication() {
+        let xs = samples();
+        let limbs = |offset: usize, len: usize| {
+            (0..len)
+                .map(|i| (xs[(offset + i) % xs.len()] % BASE as u128) as u32)
+                .collect::<Vec<u32>>()
+        };
+
+        [0, 1, 2, 31, 32, 33, 64, 101, 257, 1000]
+            .iter()
+            .for_each(|&n| {
+                let a = BigUint::from_limbs(limbs(n + 3, n));
+                assert_eq!(a.square(), &a * &a, "{} limbs", n);
+            });
+
+        let nines = BigUint::from(10u32).pow(9 * 40) - BigUint::one();
+        assert_eq!(nines.square(), &nines * &nines);
+        samples().iter().for_each(|&x| {
+            let x = x >> 64;
+            assert_eq!(BigUint::from(x).square(), BigUint::from(x * x));
+        });
+    }
+
+    #[test]
+    fn karatsuba_handles_zero_limbs() {
+        let a = BigUint::from(10u32).pow(9 * 100);
+        let b = &a + BigUint::one();
+        assert_eq!(&a * &b, BigUint::from(10u32).pow(1800) + &a);
+        assert_eq!((&b * &b).to_string().len(), 1801);
+    }
+
+    #[test]
+    fn can_compute_large_powers_of_two() {
+        assert_eq!(digit_sum(&BigUint::from(2u32).pow(15)), 26);
+        assert_eq!(digit_sum(&BigUint::from(2u32).pow(1000)), 1366);
+
+        let huge = BigUint::from(2u32).pow(100_000);
+        assert_eq!(huge.to_string().len(), 30_103);
+        assert_eq!(&huge % 1_000_000_000, 883_109_376);
+    }
+
+    #[test]
+    fn factorial_matches_running_product() {
+        let mut running = BigUint::one();
+        (0..300u64).for_each(|n| {
+            if n > 0 {
+                running *= BigUint::from(n);
+            }
+            assert_eq!(factorial(n), running, "{}!", n);
+        });
+    }
+
+    #[test]
+    fn product_of_prime_powers_matches_repeated_multiplication() {
+        let factors = [
+            (2, 0),
+            (2, 64),
+            (3, 41),
+            (3, 80),
+            (5, 1),
+            (4_294_967_291, 5),
+            (7, 2),
+        ];
+        let mut expected = BigUint::one();
+        factors.iter().for_each(|&(p, e)| {
+            (0..e).for_each(|_| expected *= BigUint::from(p));
+        });
+        assert_eq!(product_of_prime_powers(factors), expected);
+        assert_eq!(product_of_prime_powers([]), BigUint::one());
+    }
+
+    #[test]
+    fn can_compute_large_factorials() {
+        assert_eq!(digit_sum(&factorial(100)), 648);
+        assert_eq!(factorial(10_000).to_string().len(), 35_660);
+    }
+
+    #[test]
+    fn can_iterate_over_decimal_digits() {
+        let n = big("12345678901234567890");
+        assert_eq!(n.digit_count(), 20);
+        assert_eq!(
+            n.digits().rev().map(|d| d.to_string()).collect::<String>(),
+            "12345678901234567890"
+        );
+        assert_eq!(n.digits().take(3).collect::<Vec<u32>>(), vec![0, 9, 8]);
+        assert_eq!(BigUint::zero().digits().collect::<Vec<u32>>(), vec![0]);
+        assert_eq!(big("1000000000").digits().len(), 10);
+    }
+
+    #[test]
+    fn can_reverse_and_concatenate_digits() {
+        assert_eq!(big("1234567890123").reverse_digits(), big("3210987654321"));
+        assert_eq!(big("1000000000").reverse_digits(), BigUint::one());
+        assert_eq!(
+            big("12").concatenate(&big("3400000000")),
+            big("123400000000")
+        );
+        assert_eq!(big("7").concatenate(&BigUint::zero()), big("70"));
+    }
+
+    #[test]
+    fn can_sum_and_multiply_iterators() {
+        let factorial_25 = (1..=25u32).map(BigUint::from).product::<BigUint>();
+        assert_eq!(factorial_25.to_string(), "15511210043330985984000000");
+
+        let sum = ["999999999", "1", "1000000000"]
+            .iter()
+            .map(|s| big(s))
+            .sum::<BigUint>();
+        assert_eq!(sum, big("2000000000"));
+    }
+
+    #[test]
+    fn assign_operators_update_in_place() {
+        let mut n = BigUint::from(10u32);
+        n += BigUint::from(5u32);
+        n *= &BigUint::from(4u32);
+        n -= BigUint::from(20u32);
+        n /= BigUint::from(8u32);
+        assert_eq!(n, BigUint::from(5u32));
+
+        n *= 1_000_000_000;
+        n /= 10;
+        assert_eq!(n, big("500000000"));
+    }
+}
@@ -0,0 +1,352 @@
+use crate::big_uint::{legendre_exponent, product_of_prime_powers, BigUint};
+use crate::integer::{Semiring, UnsignedInt};
+use crate::mod_int::Modulus;
+use crate::modular::gcd;
+use crate::primality::is_prime;
+use crate::segmented_sieve::SegmentedSieve;
+
+// C(n, i + 1) = C(n, i) (n - i) / (i + 1). Dividing c by g = gcd(c, i + 1) first leaves
+// (i + 1) / g coprime to c / g, so it must divide n - i, and the product of the two quotients
+// is exactly C(n, i + 1). Nothing larger than the result is ever formed.
+fn next_binomial<T: UnsignedInt>(c: T, n: T, i: T) -> Option<T> {
+    let denominator = i + T::ONE;
+    let g = gcd(c, denominator);
+    (c / g).checked_mul((n - i) / (denominator / g))
+}
+
+// None only if C(n, k) itself does not fit in T. C(n, k) = 0 for k > n.
+pub fn binomial<T: UnsignedInt>(n: T, k: T) -> Option<T> {
+    if k > n {
+        return Some(T::ZERO);
+    }
+
+    let k = k.min(n - k);
+    let mut c = T::ONE;
+    let mut i = T::ZERO;
+    while i < k {
+        c = next_binomial(c, n, i)?;
+        i += T::ONE;
+    }
+    Some(c)
+}
+
+// Row n of Pascal's triangle, or None if its middle entry does not fit in T.
+pub fn pascal_row<T: UnsignedInt>(n: T) -> Option<Vec<T>> {
+    let mut row = vec![T::ONE];
+    let mut i = T::ZERO;
+    while i < n {
+        let next = next_binomial(*row.last().expect("Row starts with 1"), n, i)?;
+        row.push(next);
+        i += T::ONE;
+    }
+    Some(row)
+}
+
+// The rows of Pascal's triangle from row 0, built by adding neighbours. `one` fixes the type,
+// so the rows can be exact with `BigUint` or residues with `DynModInt::one(modulus)`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct PascalRows<T: Semiring> {
+    row: Vec<T>,
+}
+
+impl<T: Semiring> PascalRows<T> {
+    pub fn new(one: T) -> Self {
+        PascalRows { row: vec![one] }
+    }
+}
+
+impl<T: Semiring> Iterator for PascalRows<T> {
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let one = self.row[0].one_like();
+        let inner = self.row.windows(2).map(|w| w[0].clone() + w[1].clone());
+        let next = std::iter::once(one.clone())
+            .chain(inner)
+            .chain(std::iter::once(one))
+            .collect();
+        Some(std::mem::replace(&mut self.row, next))
+    }
+}
+
+// (k_1 + ... + k_m)! / (k_1! ... k_m!), as a product of binomials whose partial products
+// never exceed the result. None on overflow.
+pub fn multinomial<T: UnsignedInt>(ks: &[T]) -> Option<T> {
+    let mut total = T::ZERO;
+    ks.iter().try_fold(T::ONE, |acc, &k| {
+        total = total.checked_add(k)?;
+        acc.checked_mul(binomial(total, k)?)
+    })
+}
+
+// C(n, k) = n! / (k! (n - k)!), with Legendre's formula giving each prime's exponent.
+pub fn binomial_big(n: u64, k: u64) -> BigUint {
+    if k > n {
+        return BigUint::zero();
+    }
+
+    multinomial_big(&[k, n - k])
+}
+
+pub fn multinomial_big(ks: &[u64]) -> BigUint {
+    let total = ks
+        .iter()
+        .try_fold(0u64, |acc, &k| acc.checked_add(k))
+        .expect("Multinomial total overflowed u64");
+
+    product_of_prime_powers(
+        SegmentedSieve::new(total as usize + 1)
+            .primes()
+            .into_iter()
+            .map(|p| {
+                let p = p as u64;
+                let denominator = ks.iter().map(|&k| legendre_exponent(k, p)).sum::<u64>();
+                (p, legendre_exponent(total, p) - denominator)
+            }),
+    )
+}
+
+// Factorials and inverse factorials mod a prime p up to some n < p, after which each binomial
+// is two multiplications. A table up to p - 1 also covers every digit in Lucas' theorem.
+#[derive(Debug, Clone)]
+pub struct BinomialTable {
+    modulus: Modulus,
+    factorials: Vec<u64>,
+    inverse_factorials: Vec<u64>,
+}
+
+impl BinomialTable {
+    pub fn new(max_n: usize, p: u64) -> Self {
+        assert!(is_prime(p), "Modulus must be prime");
+        assert!((max_n as u64) < p, "Factorials from p on are zero mod p");
+
+        let modulus = Modulus::new(p);
+        let mut factorials = vec![modulus.to_residue(1); max_n + 1];
+        for i in 1..=max_n {
+            factorials[i] = modulus.mul(factorials[i - 1], modulus.to_residue(i as u64));
+        }
+
+        // 1 / (i - 1)! = i / i!, so one Fermat inverse at the top gives all the others.
+        let mut inverse_factorials = vec![0; max_n + 1];
+        inverse_factorials[max_n] = modulus.pow(factorials[max_n], p - 2);
+        for i in (1..=max_n).rev() {
+            inverse_factorials[i - 1] =
+                modulus.mul(inverse_factorials[i], modulus.to_residue(i as u64));
+        }
+
+        BinomialTable {
+            modulus,
+            factorials,
+            inverse_factorials,
+        }
+    }
+
+    pub fn max_n(&self) -> usize {
+        self.factorials.len() - 1
+    }
+
+    fn binomial_residue(&self, n: usize, k: usize) -> u64 {
+        assert!(n <= self.max_n(), "n is past the end of the table");
+        if k > n {
+            return 0;
+        }
+
+        let denominator = self
+            .modulus
+            .mul(self.inverse_factorials[k], self.inverse_factorials[n - k]);
+        self.modulus.mul(self.factorials[n], denominator)
+    }
+
+    pub fn factorial(&self, n: usize) -> u64 {
+        self.modulus.from_residue(self.factorials[n])
+    }
+
+    pub fn binomial(&self, n: usize, k: usize) -> u64 {
+        self.modulus.from_residue(self.binomial_residue(n, k))
+    }
+
+    pub fn multinomial(&self, ks: &[usize]) -> u64 {
+        let total = ks.iter().sum::<usize>();
+        assert!(total <= self.max_n(), "Total is past the end of the table");
+
+        let residue = ks.iter().fold(self.factorials[total], |acc, &k| {
+            self.modulus.mul(acc, self.inverse_factorials[k])
+        });
+        self.modulus.from_residue(residue)
+    }
+
+    // Lucas' theorem for any n, which needs the table to reach p - 1.
+    pub fn binomial_lucas(&self, n: u64, k: u64) -> u64 {
+        let p = self.modulus.modulus();
+        assert!(
+            self.max_n() as u64 == p - 1,
+            "Lucas' theorem needs factorials up to p - 1"
+        );
+
+        let residue = lucas_digits(n, k, p)
+            .try_fold(self.modulus.to_residue(1), |acc, (n_i, k_i)| {
+                let c = self.binomial_residue(n_i as usize, k_i as usize);
+                (c != 0).then(|| self.modulus.mul(acc, c))
+            })
+            .unwrap_or(0);
+        self.modulus.from_residue(residue)
+    }
+}
+
+// Pairs of base-p digits of n and k, least significant first, until both run out.
+fn lucas_digits(n: u64, k: u64, p: u64) -> impl Iterator<Item = (u64, u64)> {
+    std::iter::successors(Some((n, k)), move |&(n, k)| Some((n / p, k / p)))
+        .take_while(|&(n, k)| n > 0 || k > 0)
+        .map(move |(n, k)| (n % p, k % p))
+}
+
+// Lucas' theorem: C(n, k) = prod C(n_i, k_i) mod p over the base-p digits of n and k. Each
+// digit binomial is formed directly, so this suits small p or few digits; a `BinomialTable`
+// up to p - 1 is faster when many binomials share one p.
+pub fn binomial_mod_prime(n: u64, k: u64, p: u64) -> u64 {
+    assert!(is_prime(p), "Modulus must be prime");
+
+    let modulus = Modulus::new(p);
+    let residue = lucas_digits(n, k, p)
+        .try_fold(modulus.to_residue(1), |acc, (n_i, k_i)| {
+            if k_i > n_i {
+                return None;
+            }
+
+            let k_i = k_i.min(n_i - k_i);
+            let (numerator, denominator) = (0..k_i).fold(
+                (modulus.to_residue(1), modulus.to_residue(1)),
+                |(numerator, denominator), i| {
+                    (
+                        modulus.mul(numerator, modulus.to_residue(n_i - i)),
+                        modulus.mul(denominator, modulus.to_residue(i + 1)),
+                    )
+                },
+            );
+            let c = modulus.mul(numerator, modulus.pow(denominator, p - 2));
+            Some(modulus.mul(acc, c))
+        })
+        .unwrap_or(0);
+    modulus.from_residue(residue)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::big_uint::factorial;
+    use crate::mod_int::DynModInt;
+
+    #[test]
+    fn binomials_match_pascals_triangle() {
+        PascalRows::new(1u64)
+            .take(60)
+            .enumerate()
+            .for_each(|(n, row)| {
+                assert_eq!(pascal_row(n as u64), Some(row.clone()));
+                row.iter()
+                    .enumerate()
+                    .for_each(|(k, &c)| assert_eq!(binomial(n as u64, k as u64), Some(c)));
+            });
+        assert_eq!(binomial(5u32, 7), Some(0));
+        assert_eq!(binomial(0u8, 0), Some(1));
+    }
+
+    #[test]
+    fn binomial_only_fails_when_the_result_overflows() {
+        assert_eq!(binomial(40u64, 20), Some(137_846_528_820));
+        assert_eq!(binomial(67u64, 33), Some(14_226_520_737_620_288_370));
+        assert_eq!(binomial(68u64, 34), None);
+        assert_eq!(binomial(68u128, 34), Some(28_453_041_475_240_576_740));
+        assert_eq!(binomial(u64::MAX, 1), Some(u64::MAX));
+        assert_eq!(binomial(u64::MAX, 2), None);
+        assert_eq!(binomial(130u128, 65), binomial_big(130, 65).to_u128());
+        assert_eq!(binomial(132u128, 66), None);
+        assert_eq!(binomial(17u8, 8), None);
+        assert_eq!(pascal_row(17u8), None);
+    }
+
+    #[test]
+    fn big_binomials_are_exact() {
+        (0..80u64).for_each(|n| {
+            (0..=n).for_each(|k| {
+                let expected = &factorial(n) / &(&factorial(k) * &factorial(n - k));
+                assert_eq!(binomial_big(n, k), expected, "C({}, {})", n, k);
+            });
+        });
+        assert_eq!(binomial_big(3, 5), BigUint::zero());
+
+        let rows = PascalRows::new(BigUint::one()).nth(200).unwrap();
+        assert_eq!(rows[100], binomial_big(200, 100));
+    }
+
+    #[test]
+    fn can_count_multinomials() {
+        assert_eq!(multinomial(&[2u64, 3, 4]), Some(1260));
+        assert_eq!(multinomial::<u64>(&[]), Some(1));
+        assert_eq!(multinomial(&[10u32, 0, 10]), binomial(20, 10));
+        assert_eq!(multinomial(&[30u64, 30, 30]), None);
+        assert_eq!(
+            multinomial_big(&[30, 30, 30]),
+            &binomial_big(90, 30) * &binomial_big(60, 30)
+        );
+    }
+
+    #[test]
+    fn table_matches_exact_values() {
+        let p = 1_000_000_007;
+        let table = BinomialTable::new(300, p);
+        (0..=300usize).step_by(7).for_each(|n| {
+            (0..=n).for_each(|k| {
+                let exact = &binomial_big(n as u64, k as u64) % p as u32;
+                assert_eq!(table.binomial(n, k), exact as u64);
+            });
+        });
+        assert_eq!(table.binomial(3, 5), 0);
+        assert_eq!(table.factorial(20), 146_326_063);
+        assert_eq!(
+            table.multinomial(&[100, 100, 100]),
+            (&multinomial_big(&[100, 100, 100]) % p as u32) as u64
+        );
+    }
+
+    #[test]
+    fn pascal_rows_work_mod_m() {
+        let modulus = Modulus::new(1 << 20);
+        let row = PascalRows::new(DynModInt::one(modulus)).nth(100).unwrap();
+        let exact = pascal_row(100u128).unwrap();
+        assert!(row
+            .iter()
+            .zip(exact)
+            .all(|(r, c)| r.value() as u128 == c % (1 << 20)));
+    }
+
+    #[test]
+    fn lucas_handles_huge_n() {
+        [2u64, 3, 5, 7, 13].iter().for_each(|&p| {
+            let table = BinomialTable::new(p as usize - 1, p);
+            (0..200u64).for_each(|n| {
+                (0..=n + 2).for_each(|k| {
+                    let exact = (&binomial_big(n, k) % p as u32) as u64;
+                    assert_eq!(
+                        binomial_mod_prime(n, k, p),
+                        exact,
+                        "C({}, {}) mod {}",
+                        n,
+                        k,
+                        p
+                    );
+                    assert_eq!(table.binomial_lucas(n, k), exact);
+                });
+            });
+        });
+
+        // 10^18 = 1000...0 in base 10^9 + 7 has digits (10^18 mod p, 10^18 div p), and C(n, 1) = n.
+        let p = 1_000_000_007;
+        assert_eq!(
+            binomial_mod_prime(1_000_000_000_000_000_000, 1, p),
+            1_000_000_000_000_000_000 % p
+        );
+        assert_eq!(binomial_mod_prime(u64::MAX, u64::MAX, 2), 1);
+        assert_eq!(binomial_mod_prime(0b1010, 0b0101, 2), 0);
+    }
+}
@@ -0,0 +1,302 @@
+use crate::big_uint::{BigUint, BigUintDigits};
+use crate::integer::UnsignedInt;
+
+// Digits of n in the given base, least significant first. Keeping both the remaining value
+// and the place value of its leading digit lets the iterator run from either end without
+// allocating, so `.rev()` gives the digits most significant first.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Digits<T: UnsignedInt> {
+    n: T,
+    base: T,
+    high: T,
+    len: usize,
+}
+
+impl<T: UnsignedInt> Digits<T> {
+    pub fn new(n: T, base: u8) -> Self {
+        assert!(base >= 2, "Base must be at least 2");
+
+        let base = T::from_u8(base);
+        let (mut high, mut len) = (T::ONE, 1);
+        while n / high >= base {
+            high *= base;
+            len += 1;
+        }
+
+        Digits { n, base, high, len }
+    }
+}
+
+impl<T: UnsignedInt> Iterator for Digits<T> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+
+        let digit = self.n % self.base;
+        self.n /= self.base;
+        self.high /= self.base;
+        self.len -= 1;
+        Some(digit.to_u128() as u32)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T: UnsignedInt> DoubleEndedIterator for Digits<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+
+        let digit = self.n / self.high;
+        self.n %= self.high;
+        self.high /= self.base;
+        self.len -= 1;
+        Some(digit.to_u128() as u32)
+    }
+}
+
+impl<T: UnsignedInt> ExactSizeIterator for Digits<T> {}
+
+pub fn digits_in_base<T: UnsignedInt>(n: T, base: u8) -> Digits<T> {
+    Digits::new(n, base)
+}
+
+pub fn digit_count_in_base<T: UnsignedInt>(n: T, base: u8) -> usize {
+    Digits::new(n, base).len()
+}
+
+pub fn from_digits_in_base<T: UnsignedInt>(
+    most_significant_first: impl IntoIterator<Item = u32>,
+    base: u8,
+) -> Option<T> {
+    let base = T::from_u8(base);
+    most_significant_first
+        .into_iter()
+        .try_fold(T::ZERO, |acc, d| {
+            acc.checked_mul(base)?.checked_add(T::from_u128(d as u128)?)
+        })
+}
+
+pub fn reverse_digits_in_base<T: UnsignedInt>(n: T, base: u8) -> Option<T> {
+    from_digits_in_base(Digits::new(n, base), base)
+}
+
+pub fn reverse_digits<T: UnsignedInt>(n: T) -> Option<T> {
+    reverse_digits_in_base(n, 10)
+}
+
+pub fn concatenate_in_base<T: UnsignedInt>(a: T, b: T, base: u8) -> Option<T> {
+    from_digits_in_base(
+        Digits::new(a, base).rev().chain(Digits::new(b, base).rev()),
+        base,
+    )
+}
+
+pub fn concatenate<T: UnsignedInt>(a: T, b: T) -> Option<T> {
+    concatenate_in_base(a, b, 10)
+}
+
+// The digits of a decimal string, most significant first and leading zeros included, read
+// in place without parsing the string into a number.
+pub fn decimal_str_digits(
+    s: &str,
+) -> impl DoubleEndedIterator<Item = u32> + ExactSizeIterator + '_ {
+    s.bytes().map(|b| {
+        assert!(b.is_ascii_digit(), "Not a decimal digit");
+        u32::from(b - b'0')
+    })
+}
+
+// Decimal digit statistics shared by the primitive integers and `BigUint`.
+pub trait DecimalDigits {
+    type Digits<'a>: DoubleEndedIterator<Item = u32> + ExactSizeIterator
+    where
+        Self: 'a;
+
+    fn decimal_digits(&self) -> Self::Digits<'_>;
+
+    fn digit_sum(&self) -> u64 {
+        self.decimal_digits().map(|d| d as u64).sum()
+    }
+
+    // None if the product overflows u64, unless a zero digit makes it 0 anyway.
+    fn digit_product(&self) -> Option<u64> {
+        let mut product = Some(1u64);
+        for d in self.decimal_digits() {
+            if d == 0 {
+                return Some(0);
+            }
+            product = product.and_then(|p| p.checked_mul(d as u64));
+        }
+        product
+    }
+
+    // How many times each of 0-9 appears, so two numbers are digit permutations of each
+    // other exactly when their signatures match.
+    fn digit_signature(&self) -> [u32; 10] {
+        let mut counts = [0; 10];
+        self.decimal_digits().for_each(|d| counts[d as usize] += 1);
+        counts
+    }
+
+    fn is_digit_permutation_of(&self, other: &Self) -> bool {
+        self.digit_signature() == other.digit_signature()
+    }
+
+    // Uses each of 1 to k exactly once, and nothing else.
+    fn is_pandigital_up_to(&self, k: u32) -> bool {
+        let counts = self.digit_signature();
+        counts[0] == 0 && (1..10).all(|d| counts[d as usize] == (d <= k) as u32)
+    }
+
+    fn is_pandigital(&self) -> bool {
+        self.is_pandigital_up_to(9)
+    }
+
+    fn is_zero_pandigital(&self) -> bool {
+        self.digit_signature() == [1; 10]
+    }
+}
+
+impl<T: UnsignedInt> DecimalDigits for T {
+    type Digits<'a>
+        = Digits<T>
+    where
+        T: 'a;
+
+    fn decimal_digits(&self) -> Digits<T> {
+        Digits::new(*self, 10)
+    }
+}
+
+impl DecimalDigits for BigUint {
+    type Digits<'a> = BigUintDigits<'a>;
+
+    fn decimal_digits(&self) -> BigUintDigits<'_> {
+        self.digits()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_iterate_over_digits_in_either_direction() {
+        assert_eq!(
+            Digits::new(1234u32, 10).collect::<Vec<u32>>(),
+            vec![4, 3, 2, 1]
+        );
+        assert_eq!(
+            Digits::new(1234u32, 10).rev().collect::<Vec<u32>>(),
+            vec![1, 2, 3, 4]
+        );
+        assert_eq!(Digits::new(0u8, 10).collect::<Vec<u32>>(), vec![0]);
+        assert_eq!(
+            Digits::new(255u8, 10).rev().collect::<Vec<u32>>(),
+            vec![2, 5, 5]
+        );
+        assert_eq!(Digits::new(u64::MAX, 10).len(), 20);
+    }
+
+    #[test]
+    fn can_mix_front_and_back() {
+        let mut d = Digits::new(123456u64, 10);
+        assert_eq!(d.next(), Some(6));
+        assert_eq!(d.next_back(), Some(1));
+        assert_eq!(d.next(), Some(5));
+        assert_eq!(d.next_back(), Some(2));
+        assert_eq!(d.collect::<Vec<u32>>(), vec![4, 3]);
+    }
+
+    #[test]
+    fn can_use_other_bases() {
+        assert_eq!(
+            digits_in_base(585u32, 2).rev().collect::<Vec<u32>>(),
+            vec![1, 0, 0, 1, 0, 0, 1, 0, 0, 1]
+        );
+        assert_eq!(
+            digits_in_base(255u8, 16).collect::<Vec<u32>>(),
+            vec![15, 15]
+        );
+        assert_eq!(digit_count_in_base(u128::MAX, 2), 128);
+        assert_eq!(digit_count_in_base(35u16, 36), 1);
+    }
+
+    #[test]
+    fn can_reverse_digits() {
+        assert_eq!(reverse_digits(1234u32), Some(4321));
+        assert_eq!(reverse_digits(1200u32), Some(21));
+        assert_eq!(reverse_digits(4_000_000_009u32), None);
+        assert_eq!(reverse_digits_in_base(0b1011u8, 2), Some(0b1101));
+    }
+
+    #[test]
+    fn can_concatenate_numbers() {
+        assert_eq!(concatenate(12u32, 345), Some(12345));
+        assert_eq!(concatenate(12u32, 0), Some(120));
+        assert_eq!(concatenate(u32::MAX, 1), None);
+        assert_eq!(concatenate_in_base(0b10u8, 0b11, 2), Some(0b1011));
+    }
+
+    #[test]
+    fn can_read_digits_of_decimal_strings() {
+        assert_eq!(
+            decimal_str_digits("00907").collect::<Vec<u32>>(),
+            vec![0, 0, 9, 0, 7]
+        );
+        assert_eq!(decimal_str_digits("123").rev().len(), 3);
+        assert_eq!(decimal_str_digits("").count(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Not a decimal digit")]
+    fn non_digits_in_strings_panic() {
+        decimal_str_digits("12a").for_each(drop);
+    }
+
+    #[test]
+    fn can_sum_and_multiply_digits() {
+        assert_eq!(9875u32.digit_sum(), 29);
+        assert_eq!(9875u32.digit_product(), Some(2520));
+        assert_eq!(0u64.digit_sum(), 0);
+        assert_eq!(BigUint::from(2u32).pow(1000).digit_sum(), 1366);
+    }
+
+    #[test]
+    fn digit_product_reports_overflow() {
+        let nines = |count: u32| BigUint::from(10u32).pow(count) - BigUint::one();
+        assert_eq!(nines(20).digit_product(), Some(9u64.pow(20)));
+        assert_eq!(nines(30).digit_product(), None);
+        assert_eq!(u64::MAX.digit_product(), Some(0));
+
+        let nines_then_zero = &nines(30) * 10u32;
+        assert_eq!(nines_then_zero.digit_product(), Some(0));
+    }
+
+    #[test]
+    fn can_detect_digit_permutations() {
+        assert!(125_874u64.is_digit_permutation_of(&251_748));
+        assert!(!125_874u64.is_digit_permutation_of(&251_749));
+        assert_eq!(1_487u32.digit_signature(), 8_147u32.digit_signature());
+        assert!(BigUint::from(41_063_625u64).is_digit_permutation_of(&BigUint::from(56_623_104u64)));
+    }
+
+    #[test]
+    fn can_check_pandigitals() {
+        assert!(192_384_576u32.is_pandigital());
+        assert!(!192_384_575u32.is_pandigital());
+        assert!(!1_923_845_760u64.is_pandigital());
+        assert!(1_406_357_289u64.is_zero_pandigital());
+        assert!(2143u32.is_pandigital_up_to(4));
+        assert!(!2143u32.is_pandigital_up_to(5));
+        assert!(!21_430u32.is_pandigital_up_to(4));
+        assert!(BigUint::from(918_273_645u64).is_pandigital());
+    }
+}
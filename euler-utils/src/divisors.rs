@@ -0,0 +1,233 @@
+use crate::factor::factorize;
+
+// Every positive integer divides 0, so 0 is rejected here and in the other divisor functions
+// rather than given a count that disagrees with `divisors`.
+pub fn divisor_count(n: u64) -> u64 {
+    assert!(n > 0, "0 has infinitely many divisors");
+
+    factorize(n)
+        .iter()
+        .map(|&(_, exponent)| exponent as u64 + 1)
+        .product()
+}
+
+// sigma_k(p^e) = 1 + p^k + p^2k + ... + p^ek; returns None if the sum does not fit in a u128.
+pub fn divisor_sigma(n: u64, k: u32) -> Option<u128> {
+    assert!(n > 0, "0 has infinitely many divisors");
+
+    factorize(n).iter().try_fold(1u128, |acc, &(p, exponent)| {
+        let p_k = (p as u128).checked_pow(k)?;
+        let mut term = 1u128;
+        let mut power = 1u128;
+        for _ in 0..exponent {
+            power = power.checked_mul(p_k)?;
+            term = term.checked_add(power)?;
+        }
+        acc.checked_mul(term)
+    })
+}
+
+// Near the top of u64 the proper divisors can add up to more than u64::MAX, hence u128. An
+// aliquot sequence ends at 0, so 0 maps to 0 here instead of being rejected.
+pub fn proper_divisor_sum(n: u64) -> u128 {
+    match n {
+        0 => 0,
+        _ => divisor_sigma(n, 1).expect("sigma_1 of a u64 fits in a u128") - n as u128,
+    }
+}
+
+pub fn is_abundant(n: u64) -> bool {
+    proper_divisor_sum(n) > n as u128
+}
+
+pub fn is_perfect(n: u64) -> bool {
+    n > 0 && proper_divisor_sum(n) == n as u128
+}
+
+pub fn amicable_partner(n: u64) -> Option<u64> {
+    let partner = u64::try_from(proper_divisor_sum(n)).ok()?;
+    (partner != n && partner > 0 && proper_divisor_sum(partner) == n as u128).then_some(partner)
+}
+
+pub fn divisors(n: u64) -> Divisors {
+    assert!(n > 0, "0 has infinitely many divisors");
+    Divisors::from_factorization(&factorize(n))
+}
+
+pub fn sorted_divisors(n: u64) -> Vec<u64> {
+    let mut divisors = divisors(n).collect::<Vec<u64>>();
+    divisors.sort_unstable();
+    divisors
+}
+
+// Walks every exponent vector (e_1, ..., e_k) with 0 <= e_i <= a_i like an odometer, so
+// divisors come out in no particular order but without any trial division.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Divisors {
+    factors: Vec<(u64, u32)>,
+    exponents: Vec<u32>,
+    current: u64,
+    done: bool,
+}
+
+impl Divisors {
+    pub fn from_factorization(factors: &[(u64, u32)]) -> Self {
+        Divisors {
+            factors: factors.to_vec(),
+            exponents: vec![0; factors.len()],
+            current: 1,
+            done: false,
+        }
+    }
+
+    pub fn divisor_count(&self) -> u64 {
+        self.factors
+            .iter()
+            .map(|&(_, exponent)| exponent as u64 + 1)
+            .product()
+    }
+}
+
+impl Iterator for Divisors {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let divisor = self.current;
+        self.done = true;
+
+        for (i, &(p, max_exponent)) in self.factors.iter().enumerate() {
+            if self.exponents[i] < max_exponent {
+                self.exponents[i] += 1;
+                self.current *= p;
+                self.done = false;
+                break;
+            }
+
+            self.current /= p.pow(max_exponent);
+            self.exponents[i] = 0;
+        }
+
+        Some(divisor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn brute_force_divisors(n: u64) -> Vec<u64> {
+        (1..=n).filter(|&d| n.is_multiple_of(d)).collect()
+    }
+
+    #[test]
+    #[should_panic(expected = "infinitely many divisors")]
+    fn divisor_count_rejects_zero() {
+        divisor_count(0);
+    }
+
+    #[test]
+    #[should_panic(expected = "infinitely many divisors")]
+    fn divisors_rejects_zero() {
+        divisors(0);
+    }
+
+    #[test]
+    #[should_panic(expected = "infinitely many divisors")]
+    fn divisor_sigma_rejects_zero() {
+        divisor_sigma(0, 1);
+    }
+
+    #[test]
+    fn both_divisor_counts_agree() {
+        (1..2000u64).for_each(|n| {
+            let divisors = divisors(n);
+            assert_eq!(divisors.divisor_count(), divisor_count(n), "{}", n);
+            assert_eq!(divisors.count() as u64, divisor_count(n), "{}", n);
+        });
+        assert_eq!(proper_divisor_sum(0), 0);
+    }
+
+    #[test]
+    fn one_is_its_only_divisor() {
+        assert_eq!(divisor_count(1), 1);
+        assert_eq!(sorted_divisors(1), vec![1]);
+        assert_eq!(proper_divisor_sum(1), 0);
+    }
+
+    #[test]
+    fn divisors_match_brute_force() {
+        (1..2000).for_each(|n| {
+            let expected = brute_force_divisors(n);
+
+            assert_eq!(sorted_divisors(n), expected, "n = {}", n);
+            assert_eq!(divisor_count(n), expected.len() as u64);
+            assert_eq!(
+                divisor_sigma(n, 1),
+                Some(expected.iter().map(|&d| d as u128).sum())
+            );
+            assert_eq!(
+                divisor_sigma(n, 2),
+                Some(expected.iter().map(|&d| (d * d) as u128).sum())
+            );
+        });
+    }
+
+    #[test]
+    fn divisor_iterator_yields_each_divisor_once() {
+        let mut ds = divisors(720).collect::<Vec<u64>>();
+        let n = ds.len();
+        ds.sort_unstable();
+        ds.dedup();
+
+        assert_eq!(ds.len(), n);
+        assert_eq!(divisors(720).count(), 30);
+        assert_eq!(divisors(720).divisor_count(), 30);
+    }
+
+    #[test]
+    fn can_count_divisors_of_large_numbers() {
+        assert_eq!(divisor_count(600_851_475_143), 16);
+        assert_eq!(divisor_count(963_761_198_400), 6720);
+        assert_eq!(divisor_count(18_446_744_073_709_551_557), 2);
+    }
+
+    #[test]
+    fn sigma_0_is_the_divisor_count() {
+        assert_eq!(divisor_sigma(720, 0), Some(30));
+    }
+
+    #[test]
+    fn sigma_reports_overflow() {
+        assert_eq!(divisor_sigma(u64::MAX, 3), None);
+        assert!(divisor_sigma(u64::MAX, 1).is_some());
+    }
+
+    #[test]
+    fn can_classify_perfect_and_abundant_numbers() {
+        assert!(is_perfect(6));
+        assert!(is_perfect(28));
+        assert!(is_perfect(8128));
+        assert!(!is_perfect(12));
+
+        assert!(is_abundant(12));
+        assert!(!is_abundant(28));
+        assert!(!is_abundant(13));
+    }
+
+    #[test]
+    fn can_find_amicable_pairs() {
+        assert_eq!(amicable_partner(220), Some(284));
+        assert_eq!(amicable_partner(284), Some(220));
+        assert_eq!(amicable_partner(6), None);
+        assert_eq!(amicable_partner(10), None);
+
+        let sum = (1..10_000)
+            .filter_map(|n| amicable_partner(n).map(|_| n))
+            .sum::<u64>();
+        assert_eq!(sum, 31626);
+    }
+}
@@ -0,0 +1,317 @@
+use crate::integer::UnsignedInt;
+use crate::mod_int::Modulus;
+use crate::modular::gcd;
+use crate::primality;
+use crate::segmented_sieve::SegmentedSieve;
+use std::sync::OnceLock;
+
+const TRIAL_DIVISION_LIMIT: usize = 1 << 12;
+
+// How many differences are multiplied together before Brent's variant takes a gcd.
+const RHO_BATCH_SIZE: u32 = 128;
+
+fn trial_division_primes() -> &'static [u64] {
+    static PRIMES: OnceLock<Vec<u64>> = OnceLock::new();
+    PRIMES.get_or_init(|| {
+        SegmentedSieve::new(TRIAL_DIVISION_LIMIT)
+            .iter()
+            .map(|p| p as u64)
+            .collect()
+    })
+}
+
+// `Arithmetic` is whatever multiplication mod n needs precomputed. For u64 that is a
+// Montgomery `Modulus`, and rho then runs on Montgomery residues: x -> x^2 + c is just as good
+// a pseudo-random map there, and gcd(x R mod n, n) = gcd(x, n) because R is coprime to odd n.
+trait FactorInt: UnsignedInt + From<u64> {
+    type Arithmetic: Copy;
+
+    fn arithmetic(n: Self) -> Self::Arithmetic;
+    fn mul_mod(self, other: Self, arithmetic: &Self::Arithmetic) -> Self;
+    fn is_prime(self) -> bool;
+}
+
+impl FactorInt for u64 {
+    type Arithmetic = Modulus;
+
+    fn arithmetic(n: Self) -> Modulus {
+        Modulus::new(n)
+    }
+
+    fn mul_mod(self, other: Self, modulus: &Modulus) -> Self {
+        modulus.mul(self, other)
+    }
+
+    fn is_prime(self) -> bool {
+        primality::is_prime(self)
+    }
+}
+
+impl FactorInt for u128 {
+    type Arithmetic = u128;
+
+    fn arithmetic(n: Self) -> u128 {
+        n
+    }
+
+    fn mul_mod(self, other: Self, m: &u128) -> Self {
+        primality::mul_mod_u128(self, other, *m)
+    }
+
+    fn is_prime(self) -> bool {
+        primality::is_prime_u128(self)
+    }
+}
+
+fn abs_diff<T: FactorInt>(a: T, b: T) -> T {
+    if a > b {
+        a - b
+    } else {
+        b - a
+    }
+}
+
+fn add_mod<T: FactorInt>(a: T, b: T, m: T) -> T {
+    if a >= m - b {
+        a - (m - b)
+    } else {
+        a + b
+    }
+}
+
+// Brent's cycle finding on x -> x^2 + c (mod n). `n` must be an odd composite.
+fn pollard_brent<T: FactorInt>(n: T) -> T {
+    let one = T::from(1);
+    let arithmetic = T::arithmetic(n);
+
+    (1..)
+        .map(T::from)
+        .find_map(|c| {
+            let f = |x: T| add_mod(x.mul_mod(x, &arithmetic), c, n);
+            let (mut x, mut y, mut ys) = (T::from(2), T::from(2), T::from(2));
+            let (mut g, mut q, mut r) = (one, one, 1u32);
+
+            while g == one {
+                x = y;
+                (0..r).for_each(|_| y = f(y));
+
+                let mut k = 0;
+                while k < r && g == one {
+                    ys = y;
+                    (0..RHO_BATCH_SIZE.min(r - k)).for_each(|_| {
+                        y = f(y);
+                        q = q.mul_mod(abs_diff(x, y), &arithmetic);
+                    });
+                    g = gcd(q, n);
+                    k += RHO_BATCH_SIZE;
+                }
+                r = r.saturating_mul(2);
+            }
+
+            if g == n {
+                loop {
+                    ys = f(ys);
+                    g = gcd(abs_diff(x, ys), n);
+                    if g != one {
+                        break;
+                    }
+                }
+            }
+
+            (g != n).then_some(g)
+        })
+        .expect("Every odd composite has a nontrivial factor")
+}
+
+fn collect_large_factors<T: FactorInt>(n: T, factors: &mut Vec<T>) {
+    if n == T::from(1) {
+        return;
+    }
+
+    if n.is_prime() {
+        factors.push(n);
+    } else {
+        let d = pollard_brent(n);
+        collect_large_factors(d, factors);
+        collect_large_factors(n / d, factors);
+    }
+}
+
+fn factorize_generic<T: FactorInt>(n: T) -> Vec<(T, u32)> {
+    let zero = T::from(0);
+    let one = T::from(1);
+    let mut factors = Vec::new();
+
+    if n <= one {
+        return vec![];
+    }
+
+    let mut n = n;
+    for &p in trial_division_primes() {
+        let p = T::from(p);
+        if p * p > n {
+            break;
+        }
+
+        let mut exponent = 0;
+        while n % p == zero {
+            n /= p;
+            exponent += 1;
+        }
+        if exponent > 0 {
+            factors.push((p, exponent));
+        }
+    }
+
+    let mut large = Vec::new();
+    collect_large_factors(n, &mut large);
+    large.sort_unstable();
+
+    for p in large {
+        match factors.last_mut() {
+            Some((q, exponent)) if *q == p => *exponent += 1,
+            _ => factors.push((p, 1)),
+        }
+    }
+
+    factors
+}
+
+pub fn factorize(n: u64) -> Vec<(u64, u32)> {
+    factorize_generic(n)
+}
+
+pub fn factorize_u128(n: u128) -> Vec<(u128, u32)> {
+    match u64::try_from(n) {
+        Ok(small) => factorize(small)
+            .into_iter()
+            .map(|(p, exponent)| (p as u128, exponent))
+            .collect(),
+        Err(_) => factorize_generic(n),
+    }
+}
+
+pub fn distinct_prime_factors(n: u64) -> Vec<u64> {
+    factorize(n).into_iter().map(|(p, _)| p).collect()
+}
+
+pub fn largest_prime_factor(n: u64) -> Option<u64> {
+    factorize(n).last().map(|&(p, _)| p)
+}
+
+pub fn smallest_prime_factor(n: u64) -> Option<u64> {
+    factorize(n).first().map(|&(p, _)| p)
+}
+
+pub fn from_factorization(factors: &[(u64, u32)]) -> u64 {
+    factors
+        .iter()
+        .map(|&(p, exponent)| p.pow(exponent))
+        .product()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zero_and_one_have_no_prime_factors() {
+        assert_eq!(factorize(0), vec![]);
+        assert_eq!(factorize(1), vec![]);
+    }
+
+    #[test]
+    fn can_factorize_small_numbers() {
+        assert_eq!(factorize(2), vec![(2, 1)]);
+        assert_eq!(factorize(12), vec![(2, 2), (3, 1)]);
+        assert_eq!(factorize(13195), vec![(5, 1), (7, 1), (13, 1), (29, 1)]);
+        assert_eq!(factorize(1024), vec![(2, 10)]);
+    }
+
+    #[test]
+    fn factorization_round_trips_for_first_10_000_integers() {
+        (1..10_000).for_each(|n| {
+            let factors = factorize(n);
+            assert_eq!(from_factorization(&factors), n);
+            assert!(factors.iter().all(|&(p, _)| primality::is_prime(p)));
+            assert!(factors.windows(2).all(|w| w[0].0 < w[1].0));
+        });
+    }
+
+    #[test]
+    fn keeps_the_cofactor_above_the_square_root() {
+        assert_eq!(
+            factorize(2 * 1_000_000_007),
+            vec![(2, 1), (1_000_000_007, 1)]
+        );
+        assert_eq!(distinct_prime_factors(14), vec![2, 7]);
+    }
+
+    #[test]
+    fn can_split_semiprimes_with_pollard_rho() {
+        let p = 4_294_967_291;
+        let q = 4_294_967_279;
+
+        assert_eq!(factorize(p * q), vec![(q, 1), (p, 1)]);
+        assert_eq!(
+            factorize(1_000_003 * 1_000_003 * 999_983),
+            vec![(999_983, 1), (1_000_003, 2)]
+        );
+    }
+
+    #[test]
+    fn can_factorize_extremes_of_u64() {
+        assert_eq!(
+            factorize(u64::MAX),
+            vec![
+                (3, 1),
+                (5, 1),
+                (17, 1),
+                (257, 1),
+                (641, 1),
+                (65537, 1),
+                (6_700_417, 1)
+            ]
+        );
+        assert_eq!(
+            factorize(18_446_744_073_709_551_557),
+            vec![(18_446_744_073_709_551_557, 1)]
+        );
+    }
+
+    #[test]
+    fn can_get_largest_prime_factor_from_problem_3() {
+        assert_eq!(largest_prime_factor(13195), Some(29));
+        assert_eq!(largest_prime_factor(600_851_475_143), Some(6857));
+        assert_eq!(largest_prime_factor(1), None);
+    }
+
+    #[test]
+    fn can_get_smallest_prime_factor() {
+        assert_eq!(smallest_prime_factor(91), Some(7));
+        assert_eq!(smallest_prime_factor(97), Some(97));
+    }
+
+    #[test]
+    fn can_factorize_u128() {
+        assert_eq!(factorize_u128(360), vec![(2, 3), (3, 2), (5, 1)]);
+        assert_eq!(
+            factorize_u128(u128::MAX),
+            vec![
+                (3, 1),
+                (5, 1),
+                (17, 1),
+                (257, 1),
+                (641, 1),
+                (65537, 1),
+                (274_177, 1),
+                (6_700_417, 1),
+                (67_280_421_310_721, 1)
+            ]
+        );
+        assert_eq!(
+            factorize_u128(18_446_744_073_709_551_557 * 1_000_000_007),
+            vec![(1_000_000_007, 1), (18_446_744_073_709_551_557, 1)]
+        );
+    }
+}
@@ -0,0 +1,349 @@
+use crate::big_uint::BigUint;
+use crate::factor::{factorize, factorize_u128};
+use crate::integer::UnsignedInt;
+use crate::mod_int::Modulus;
+use crate::modular::lcm;
+
+// Sequences with x_(n+1) = c x_n + x_(n-1), ending at the first term that overflows T. The
+// Fibonacci and Lucas numbers have c = 1, and every third Fibonacci number is even, with
+// F(3k + 3) = 4 F(3k) + F(3k - 3), so the even terms alone have c = 4.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Fibonacci<T: UnsignedInt> {
+    current: Option<T>,
+    next: Option<T>,
+    multiplier: T,
+}
+
+impl<T: UnsignedInt> Fibonacci<T> {
+    // F(0), F(1), F(2), ... = 0, 1, 1, 2, 3, ...
+    pub fn new() -> Self {
+        Fibonacci::from_seeds(T::ZERO, T::ONE)
+    }
+
+    // L(0), L(1), L(2), ... = 2, 1, 3, 4, 7, ...
+    pub fn lucas() -> Self {
+        Fibonacci::from_seeds(T::from_u8(2), T::ONE)
+    }
+
+    // F(0), F(3), F(6), ... = 0, 2, 8, 34, ...
+    pub fn evens() -> Self {
+        Fibonacci {
+            current: Some(T::ZERO),
+            next: Some(T::from_u8(2)),
+            multiplier: T::from_u8(4),
+        }
+    }
+
+    pub fn from_seeds(first: T, second: T) -> Self {
+        Fibonacci {
+            current: Some(first),
+            next: Some(second),
+            multiplier: T::ONE,
+        }
+    }
+}
+
+impl<T: UnsignedInt> Default for Fibonacci<T> {
+    fn default() -> Self {
+        Fibonacci::new()
+    }
+}
+
+impl<T: UnsignedInt> Iterator for Fibonacci<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.current?;
+        self.current = self.next;
+        self.next = self.next.and_then(|next| {
+            next.checked_mul(self.multiplier)
+                .and_then(|x| x.checked_add(current))
+        });
+        Some(current)
+    }
+}
+
+// Fast doubling: from (F(k), F(k + 1)),
+//     F(2k) = F(k) (2 F(k + 1) - F(k)),  F(2k + 1) = F(k)^2 + F(k + 1)^2,
+// and one more addition steps to k + 1, so walking the bits of n from the top gives
+// (F(n), F(n + 1)) in O(log n) steps. `double` and `add` do the arithmetic and return None
+// on overflow.
+fn fibonacci_pair<T>(
+    n: u128,
+    zero: T,
+    one: T,
+    double: impl Fn(&T, &T) -> Option<(T, T)>,
+    add: impl Fn(&T, &T) -> Option<T>,
+) -> Option<(T, T)> {
+    (0..u128::BITS - n.leading_zeros())
+        .rev()
+        .try_fold((zero, one), |(a, b), bit| {
+            let (c, d) = double(&a, &b)?;
+            if (n >> bit) & 1 == 1 {
+                let e = add(&c, &d)?;
+                Some((d, e))
+            } else {
+                Some((c, d))
+            }
+        })
+}
+
+fn checked_pair<T: UnsignedInt>(n: u64) -> Option<(T, T)> {
+    fibonacci_pair(
+        n as u128,
+        T::ZERO,
+        T::ONE,
+        |&a, &b| {
+            let c = a.checked_mul(b.checked_add(b)? - a)?;
+            let d = a.checked_mul(a)?.checked_add(b.checked_mul(b)?)?;
+            Some((c, d))
+        },
+        |&a, &b| a.checked_add(b),
+    )
+}
+
+// F(n), or None if it does not fit in T. The last doubling step is done by hand so that
+// F(n + 1) is never needed, which lets the largest Fibonacci number that fits come out.
+pub fn fibonacci<T: UnsignedInt>(n: u64) -> Option<T> {
+    let (a, b) = checked_pair::<T>(n / 2)?;
+    if n.is_multiple_of(2) {
+        a.checked_mul(b.checked_add(b)? - a)
+    } else {
+        a.checked_mul(a)?.checked_add(b.checked_mul(b)?)
+    }
+}
+
+// L(n) = F(n + 1) + F(n - 1) = F(n) + 2 F(n - 1), whose terms are each smaller than L(n).
+pub fn lucas<T: UnsignedInt>(n: u64) -> Option<T> {
+    if n == 0 {
+        return Some(T::from_u8(2));
+    }
+
+    let (a, b) = checked_pair::<T>(n - 1)?;
+    b.checked_add(a.checked_add(a)?)
+}
+
+fn modular_pair(n: u128, modulus: &Modulus) -> (u64, u64) {
+    fibonacci_pair(
+        n,
+        0,
+        modulus.to_residue(1),
+        |&a, &b| {
+            let c = modulus.mul(a, modulus.sub(modulus.add(b, b), a));
+            let d = modulus.add(modulus.mul(a, a), modulus.mul(b, b));
+            Some((c, d))
+        },
+        |&a, &b| Some(modulus.add(a, b)),
+    )
+    .expect("Modular arithmetic cannot overflow")
+}
+
+pub fn fibonacci_mod(n: u128, m: u64) -> u64 {
+    let modulus = Modulus::new(m);
+    modulus.from_residue(modular_pair(n, &modulus).0)
+}
+
+// L(n) = 2 F(n + 1) - F(n).
+pub fn lucas_mod(n: u128, m: u64) -> u64 {
+    let modulus = Modulus::new(m);
+    let (a, b) = modular_pair(n, &modulus);
+    modulus.from_residue(modulus.sub(modulus.add(b, b), a))
+}
+
+fn big_pair(n: u64) -> (BigUint, BigUint) {
+    fibonacci_pair(
+        n as u128,
+        BigUint::zero(),
+        BigUint::one(),
+        |a, b| {
+            let c = a * &(&(b * 2) - a);
+            let d = &a.square() + &b.square();
+            Some((c, d))
+        },
+        |a, b| Some(a + b),
+    )
+    .expect("BigUint arithmetic cannot overflow")
+}
+
+pub fn fibonacci_big(n: u64) -> BigUint {
+    big_pair(n).0
+}
+
+pub fn lucas_big(n: u64) -> BigUint {
+    let (a, b) = big_pair(n);
+    &(&b * 2) - &a
+}
+
+// Zeckendorf's theorem: every positive integer is a unique sum of non-consecutive Fibonacci
+// numbers, and taking the largest one that fits at each step finds it. Largest first; empty
+// for zero.
+pub fn zeckendorf(n: u64) -> Vec<u64> {
+    let fibonaccis = Fibonacci::<u64>::new()
+        .skip(2)
+        .take_while(|&f| f <= n)
+        .collect::<Vec<u64>>();
+
+    let mut remaining = n;
+    let mut terms = Vec::new();
+    for f in fibonaccis.into_iter().rev() {
+        if f <= remaining {
+            remaining -= f;
+            terms.push(f);
+        }
+    }
+    terms
+}
+
+// A multiple of the Pisano period of p: 3 for 2, 20 for 5, and otherwise p - 1 or 2 (p + 1)
+// depending on whether 5 is a square mod p, that is on p mod 5.
+fn pisano_multiple_for_prime(p: u64) -> u128 {
+    match p {
+        2 => 3,
+        5 => 20,
+        _ if p % 5 == 1 || p % 5 == 4 => p as u128 - 1,
+        _ => 2 * (p as u128 + 1),
+    }
+}
+
+// The period of the Fibonacci numbers mod m. pi(p^k) divides p^(k - 1) pi(p), so the lcm of
+// those bounds is a multiple of pi(m), and dividing out primes while the sequence still
+// returns to (0, 1) leaves exactly pi(m).
+pub fn pisano_period(m: u64) -> u128 {
+    assert!(m > 0, "Modulus must be positive");
+    if m == 1 {
+        return 1;
+    }
+
+    let mut period = factorize(m)
+        .into_iter()
+        .map(|(p, exponent)| pisano_multiple_for_prime(p) * (p as u128).pow(exponent - 1))
+        .try_fold(1u128, lcm)
+        .expect("At most m times 3 per distinct prime, which fits in a u128");
+
+    let modulus = Modulus::new(m);
+    let one = modulus.to_residue(1);
+    let is_period = |n: u128| modular_pair(n, &modulus) == (0, one);
+
+    factorize_u128(period)
+        .into_iter()
+        .for_each(|(q, exponent)| {
+            for _ in 0..exponent {
+                if !is_period(period / q) {
+                    break;
+                }
+                period /= q;
+            }
+        });
+
+    period
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_iterate_over_fibonacci_and_lucas_numbers() {
+        assert_eq!(
+            Fibonacci::<u32>::new().take(10).collect::<Vec<u32>>(),
+            vec![0, 1, 1, 2, 3, 5, 8, 13, 21, 34]
+        );
+        assert_eq!(
+            Fibonacci::<u32>::lucas().take(8).collect::<Vec<u32>>(),
+            vec![2, 1, 3, 4, 7, 11, 18, 29]
+        );
+        assert_eq!(Fibonacci::<u64>::new().count(), 94);
+        assert_eq!(Fibonacci::<u8>::new().last(), Some(233));
+    }
+
+    #[test]
+    fn evens_are_every_third_fibonacci_number() {
+        let expected = Fibonacci::<u64>::new().step_by(3).collect::<Vec<u64>>();
+        assert_eq!(Fibonacci::<u64>::evens().collect::<Vec<u64>>(), expected);
+
+        let sum = Fibonacci::<u64>::evens()
+            .take_while(|&f| f <= 4_000_000)
+            .sum::<u64>();
+        assert_eq!(sum, 4_613_732);
+    }
+
+    #[test]
+    fn fast_doubling_matches_iteration() {
+        Fibonacci::<u128>::new()
+            .enumerate()
+            .for_each(|(n, f)| assert_eq!(fibonacci::<u128>(n as u64), Some(f), "F({})", n));
+        Fibonacci::<u128>::lucas()
+            .enumerate()
+            .for_each(|(n, l)| assert_eq!(lucas::<u128>(n as u64), Some(l), "L({})", n));
+    }
+
+    #[test]
+    fn fast_doubling_reaches_the_largest_value_that_fits() {
+        assert_eq!(fibonacci::<u64>(93), Some(12_200_160_415_121_876_738));
+        assert_eq!(fibonacci::<u64>(94), None);
+        assert!(fibonacci::<u128>(186).is_some());
+        assert_eq!(fibonacci::<u128>(187), None);
+        assert_eq!(lucas::<u64>(92), Some(16_860_207_025_497_407_047));
+        assert_eq!(lucas::<u64>(93), None);
+    }
+
+    #[test]
+    fn modular_and_big_variants_agree() {
+        (0..300u64).for_each(|n| {
+            let big = fibonacci_big(n);
+            assert_eq!(
+                fibonacci_mod(n as u128, 1_000_000_007),
+                (&big % 1_000_000_007) as u64
+            );
+            assert_eq!(
+                fibonacci_mod(n as u128, 1 << 40),
+                (&big % &BigUint::from(1u64 << 40)).to_u64().unwrap()
+            );
+            assert_eq!(
+                lucas_mod(n as u128, 999_999_937),
+                (&lucas_big(n) % 999_999_937) as u64
+            );
+        });
+        assert_eq!(fibonacci_mod(10u128.pow(30), 1), 0);
+    }
+
+    #[test]
+    fn problem_25_first_fibonacci_with_1000_digits() {
+        let index = (1..)
+            .find(|&n| fibonacci_big(n).digit_count() >= 1000)
+            .unwrap();
+        assert_eq!(index, 4782);
+    }
+
+    #[test]
+    fn can_find_zeckendorf_representations() {
+        assert_eq!(zeckendorf(0), vec![]);
+        assert_eq!(zeckendorf(100), vec![89, 8, 3]);
+        assert_eq!(zeckendorf(u64::MAX).iter().sum::<u64>(), u64::MAX);
+
+        (1..2000u64).for_each(|n| {
+            let terms = zeckendorf(n);
+            assert_eq!(terms.iter().sum::<u64>(), n);
+            assert!(terms.windows(2).all(|w| w[0] > 2 * w[1]), "{:?}", terms);
+        });
+    }
+
+    #[test]
+    fn pisano_periods_match_brute_force() {
+        let brute_force = |m: u64| {
+            let (mut a, mut b, mut period) = (0, 1 % m, 0);
+            loop {
+                (a, b) = (b, (a + b) % m);
+                period += 1;
+                if (a, b) == (0, 1 % m) {
+                    return period;
+                }
+            }
+        };
+
+        (1..=500u64).for_each(|m| assert_eq!(pisano_period(m), brute_force(m), "pi({})", m));
+        assert_eq!(pisano_period(10), 60);
+        assert_eq!(pisano_period(1_000_000_000), 1_500_000_000);
+        assert_eq!(pisano_period(1_000_000_007), 2_000_000_016);
+    }
+}
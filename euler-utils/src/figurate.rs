@@ -0,0 +1,243 @@
+use crate::integer::UnsignedInt;
+
+// P(s, n) = n + (s - 2) * n(n - 1) / 2. Halving whichever of n, n - 1 is even first means
+// nothing overflows unless the result itself does.
+pub fn polygonal<T: UnsignedInt>(sides: u8, n: T) -> Option<T> {
+    assert!(sides >= 3, "A polygon needs at least 3 sides");
+
+    if n.is_zero() {
+        return Some(T::ZERO);
+    }
+
+    let two = T::from_u8(2);
+    let previous = n - T::ONE;
+    let pairs = if n.is_even() {
+        (n / two).checked_mul(previous)?
+    } else {
+        n.checked_mul(previous / two)?
+    };
+
+    pairs.checked_mul(T::from_u8(sides - 2))?.checked_add(n)
+}
+
+pub fn triangular<T: UnsignedInt>(n: T) -> Option<T> {
+    polygonal(3, n)
+}
+
+pub fn square<T: UnsignedInt>(n: T) -> Option<T> {
+    polygonal(4, n)
+}
+
+pub fn pentagonal<T: UnsignedInt>(n: T) -> Option<T> {
+    polygonal(5, n)
+}
+
+pub fn hexagonal<T: UnsignedInt>(n: T) -> Option<T> {
+    polygonal(6, n)
+}
+
+pub fn heptagonal<T: UnsignedInt>(n: T) -> Option<T> {
+    polygonal(7, n)
+}
+
+pub fn octagonal<T: UnsignedInt>(n: T) -> Option<T> {
+    polygonal(8, n)
+}
+
+// Solving P(s, n) = x gives n = ((s - 4) + sqrt(8(s - 2)x + (s - 4)^2)) / (2(s - 2)),
+// so x is s-gonal exactly when that square root and division are both exact.
+pub fn polygonal_index<T: UnsignedInt>(sides: u8, x: T) -> Option<T> {
+    assert!(sides >= 3, "A polygon needs at least 3 sides");
+
+    if x.is_zero() {
+        return Some(T::ZERO);
+    }
+
+    let s = sides as u128;
+    let discriminant = x
+        .to_u128()
+        .checked_mul(8 * (s - 2))
+        .and_then(|d| d.checked_add(s.abs_diff(4).pow(2)));
+
+    match discriminant {
+        Some(d) => {
+            let root = d.isqrt();
+            if root * root != d || !(root + s - 4).is_multiple_of(2 * (s - 2)) {
+                return None;
+            }
+            T::from_u128((root + s - 4) / (2 * (s - 2)))
+        }
+        None => polygonal_index_by_search(sides, x),
+    }
+}
+
+// Only reached for u128 inputs too large for the discriminant to fit in a u128.
+fn polygonal_index_by_search<T: UnsignedInt>(sides: u8, x: T) -> Option<T> {
+    let (mut low, mut high) = (T::ZERO, x.isqrt().checked_mul(T::from_u8(2))?);
+
+    while low < high {
+        let mid = low + (high - low) / T::from_u8(2);
+        match polygonal(sides, mid) {
+            Some(value) if value < x => low = mid + T::ONE,
+            _ => high = mid,
+        }
+    }
+
+    (polygonal(sides, low) == Some(x)).then_some(low)
+}
+
+pub fn is_polygonal<T: UnsignedInt>(sides: u8, x: T) -> bool {
+    polygonal_index(sides, x).is_some()
+}
+
+pub fn is_triangular<T: UnsignedInt>(x: T) -> bool {
+    is_polygonal(3, x)
+}
+
+pub fn is_square<T: UnsignedInt>(x: T) -> bool {
+    is_polygonal(4, x)
+}
+
+pub fn is_pentagonal<T: UnsignedInt>(x: T) -> bool {
+    is_polygonal(5, x)
+}
+
+pub fn is_hexagonal<T: UnsignedInt>(x: T) -> bool {
+    is_polygonal(6, x)
+}
+
+pub fn is_heptagonal<T: UnsignedInt>(x: T) -> bool {
+    is_polygonal(7, x)
+}
+
+pub fn is_octagonal<T: UnsignedInt>(x: T) -> bool {
+    is_polygonal(8, x)
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct PolygonalNumbers<T: UnsignedInt> {
+    sides: u8,
+    n: Option<T>,
+}
+
+impl<T: UnsignedInt> PolygonalNumbers<T> {
+    pub fn new(sides: u8) -> Self {
+        assert!(sides >= 3, "A polygon needs at least 3 sides");
+        PolygonalNumbers {
+            sides,
+            n: Some(T::ONE),
+        }
+    }
+}
+
+impl<T: UnsignedInt> Iterator for PolygonalNumbers<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let n = self.n?;
+        let value = polygonal(self.sides, n);
+
+        self.n = value.and_then(|_| n.checked_add(T::ONE));
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_few_of_each_shape() {
+        let first = |sides| {
+            (1..=5u32)
+                .map(|n| polygonal(sides, n).unwrap())
+                .collect::<Vec<u32>>()
+        };
+
+        assert_eq!(first(3), vec![1, 3, 6, 10, 15]);
+        assert_eq!(first(4), vec![1, 4, 9, 16, 25]);
+        assert_eq!(first(5), vec![1, 5, 12, 22, 35]);
+        assert_eq!(first(6), vec![1, 6, 15, 28, 45]);
+        assert_eq!(first(7), vec![1, 7, 18, 34, 55]);
+        assert_eq!(first(8), vec![1, 8, 21, 40, 65]);
+    }
+
+    #[test]
+    fn named_functions_match_polygonal() {
+        (0..100u64).for_each(|n| {
+            assert_eq!(triangular(n), polygonal(3, n));
+            assert_eq!(square(n), Some(n * n));
+            assert_eq!(pentagonal(n), Some(n * (3 * n).saturating_sub(1) / 2));
+            assert_eq!(hexagonal(n), polygonal(6, n));
+            assert_eq!(heptagonal(n), polygonal(7, n));
+            assert_eq!(octagonal(n), polygonal(8, n));
+        });
+    }
+
+    #[test]
+    fn does_not_overflow_before_the_result_does() {
+        assert_eq!(triangular(65_535u32), Some(2_147_450_880));
+        assert_eq!(triangular(92_681u32), Some(4_294_930_221));
+        assert_eq!(triangular(92_682u32), None);
+        assert_eq!(triangular(u64::MAX), None);
+        assert_eq!(square(255u8), None);
+        assert_eq!(square(15u8), Some(225));
+    }
+
+    #[test]
+    fn inverse_recovers_the_index() {
+        (3..=8u8).for_each(|sides| {
+            (0..2000u64).for_each(|n| {
+                let x = polygonal(sides, n).unwrap();
+                assert_eq!(polygonal_index(sides, x), Some(n), "P({}, {})", sides, n);
+                assert!(is_polygonal(sides, x));
+            });
+        });
+    }
+
+    #[test]
+    fn inverse_rejects_numbers_between_shapes() {
+        (3..=8u8).for_each(|sides| {
+            let shaped = PolygonalNumbers::<u32>::new(sides)
+                .take_while(|&x| x < 5000)
+                .collect::<Vec<u32>>();
+
+            (1..5000u32).for_each(|x| assert_eq!(is_polygonal(sides, x), shaped.contains(&x)));
+        });
+    }
+
+    #[test]
+    fn named_tests_from_problems_42_44_and_45() {
+        assert!(is_triangular(55u32));
+        assert!(!is_triangular(56u32));
+        assert!(is_pentagonal(5_482_660u64));
+        assert!(is_hexagonal(40_755u64));
+        assert!(is_pentagonal(40_755u64));
+        assert!(is_triangular(40_755u64));
+        assert!(is_square(144u16));
+        assert!(is_heptagonal(5_688u32));
+        assert!(is_octagonal(8_008u32));
+        assert!(is_hexagonal(8_128u32));
+        assert!(!is_octagonal(8_128u32));
+    }
+
+    #[test]
+    fn inverse_works_at_the_top_of_u128() {
+        let n = 1u128 << 62;
+        let x = triangular(n).unwrap();
+
+        assert_eq!(polygonal_index(3, x), Some(n));
+        assert!(!is_triangular(x + 1));
+
+        let big = octagonal(1u128 << 61).unwrap();
+        assert_eq!(polygonal_index(8, big), Some(1 << 61));
+        assert!(!is_octagonal(big - 1));
+    }
+
+    #[test]
+    fn iterator_stops_at_overflow() {
+        let all = PolygonalNumbers::<u8>::new(3).collect::<Vec<u8>>();
+        assert_eq!(all.last(), Some(&253));
+        assert_eq!(all.len(), 22);
+    }
+}
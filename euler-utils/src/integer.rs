@@ -0,0 +1,260 @@
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
+use std::ops::{
+    Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Shl, Shr, Sub, SubAssign,
+};
+
+pub trait UnsignedInt:
+    Copy
+    + Ord
+    + Hash
+    + Debug
+    + Display
+    + Default
+    + Send
+    + Sync
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+    + AddAssign
+    + SubAssign
+    + MulAssign
+    + DivAssign
+    + RemAssign
+    + Shl<u32, Output = Self>
+    + Shr<u32, Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+    const MAX: Self;
+    const BITS: u32;
+
+    fn from_u8(x: u8) -> Self;
+    fn from_u128(x: u128) -> Option<Self>;
+    fn to_u128(self) -> u128;
+    fn checked_add(self, other: Self) -> Option<Self>;
+    fn checked_sub(self, other: Self) -> Option<Self>;
+    fn checked_mul(self, other: Self) -> Option<Self>;
+    fn isqrt(self) -> Self;
+    fn trailing_zeros(self) -> u32;
+
+    fn is_zero(self) -> bool {
+        self == Self::ZERO
+    }
+
+    fn is_even(self) -> bool {
+        self % Self::from_u8(2) == Self::ZERO
+    }
+}
+
+macro_rules! impl_unsigned_int {
+    ($($t:ty),*) => {
+        $(
+            impl UnsignedInt for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+                const MAX: Self = <$t>::MAX;
+                const BITS: u32 = <$t>::BITS;
+
+                fn from_u8(x: u8) -> Self {
+                    x as $t
+                }
+
+                fn from_u128(x: u128) -> Option<Self> {
+                    <$t>::try_from(x).ok()
+                }
+
+                fn to_u128(self) -> u128 {
+                    self as u128
+                }
+
+                fn checked_add(self, other: Self) -> Option<Self> {
+                    <$t>::checked_add(self, other)
+                }
+
+                fn checked_sub(self, other: Self) -> Option<Self> {
+                    <$t>::checked_sub(self, other)
+                }
+
+                fn checked_mul(self, other: Self) -> Option<Self> {
+                    <$t>::checked_mul(self, other)
+                }
+
+                fn isqrt(self) -> Self {
+                    <$t>::isqrt(self)
+                }
+
+                fn trailing_zeros(self) -> u32 {
+                    <$t>::trailing_zeros(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_unsigned_int!(u8, u16, u32, u64, u128, usize);
+
+// The integer types an exact `Ratio` can be built on: primitives for speed, `BigInt` when
+// numerators and denominators outgrow them.
+pub trait SignedInt:
+    Clone
+    + Ord
+    + Hash
+    + Debug
+    + Display
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+    + Neg<Output = Self>
+{
+    fn zero() -> Self;
+    fn one() -> Self;
+
+    fn is_zero(&self) -> bool {
+        *self == Self::zero()
+    }
+
+    fn is_negative(&self) -> bool {
+        *self < Self::zero()
+    }
+
+    fn abs(&self) -> Self {
+        if self.is_negative() {
+            -self.clone()
+        } else {
+            self.clone()
+        }
+    }
+
+    fn gcd(&self, other: &Self) -> Self {
+        let (mut a, mut b) = (self.abs(), other.abs());
+        while !b.is_zero() {
+            let r = a % b.clone();
+            a = b;
+            b = r;
+        }
+        a
+    }
+
+    // Rounds towards negative infinity, where `/` on primitives rounds towards zero.
+    fn div_floor(&self, other: &Self) -> Self {
+        let q = self.clone() / other.clone();
+        let r = self.clone() % other.clone();
+        if !r.is_zero() && (r.is_negative() != other.is_negative()) {
+            q - Self::one()
+        } else {
+            q
+        }
+    }
+}
+
+macro_rules! impl_signed_int {
+    ($($t:ty),*) => {
+        $(
+            impl SignedInt for $t {
+                fn zero() -> Self {
+                    0
+                }
+
+                fn one() -> Self {
+                    1
+                }
+            }
+        )*
+    };
+}
+
+impl_signed_int!(i8, i16, i32, i64, i128, isize);
+
+// Just enough structure for matrices and linear recurrences. The identities come from an
+// existing value rather than an associated function so that a modulus chosen at runtime
+// carries over to them.
+pub trait Semiring: Clone + Add<Output = Self> + Mul<Output = Self> {
+    fn zero_like(&self) -> Self;
+    fn one_like(&self) -> Self;
+}
+
+macro_rules! impl_semiring {
+    ($($t:ty),*) => {
+        $(
+            impl Semiring for $t {
+                fn zero_like(&self) -> Self {
+                    0
+                }
+
+                fn one_like(&self) -> Self {
+                    1
+                }
+            }
+        )*
+    };
+}
+
+impl_semiring!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip<T: UnsignedInt>(x: u128) -> Option<u128> {
+        T::from_u128(x).map(T::to_u128)
+    }
+
+    #[test]
+    fn constants_match_primitives() {
+        assert_eq!(<u8 as UnsignedInt>::MAX, 255);
+        assert_eq!(<u32 as UnsignedInt>::BITS, 32);
+        assert_eq!(<u64 as UnsignedInt>::ZERO, 0);
+        assert_eq!(<u128 as UnsignedInt>::ONE, 1);
+    }
+
+    #[test]
+    fn can_convert_through_u128() {
+        assert_eq!(round_trip::<u8>(255), Some(255));
+        assert_eq!(round_trip::<u8>(256), None);
+        assert_eq!(round_trip::<u64>(u64::MAX as u128), Some(u64::MAX as u128));
+        assert_eq!(round_trip::<u128>(u128::MAX), Some(u128::MAX));
+    }
+
+    #[test]
+    fn checked_arithmetic_reports_overflow() {
+        assert_eq!(UnsignedInt::checked_add(250u8, 5), Some(255));
+        assert_eq!(UnsignedInt::checked_add(250u8, 6), None);
+        assert_eq!(UnsignedInt::checked_sub(0u32, 1), None);
+        assert_eq!(UnsignedInt::checked_mul(1u64 << 32, 1 << 32), None);
+    }
+
+    #[test]
+    fn can_take_integer_square_roots() {
+        assert_eq!(UnsignedInt::isqrt(99u32), 9);
+        assert_eq!(UnsignedInt::isqrt(100u64), 10);
+        assert_eq!(UnsignedInt::isqrt(u128::MAX), u64::MAX as u128);
+    }
+
+    #[test]
+    fn signed_gcd_ignores_signs() {
+        assert_eq!(SignedInt::gcd(&12i64, &-18), 6);
+        assert_eq!(SignedInt::gcd(&-7i32, &0), 7);
+        assert_eq!(SignedInt::gcd(&0i128, &0), 0);
+    }
+
+    #[test]
+    fn div_floor_rounds_down() {
+        assert_eq!(SignedInt::div_floor(&7i64, &2), 3);
+        assert_eq!(SignedInt::div_floor(&-7i64, &2), -4);
+        assert_eq!(SignedInt::div_floor(&7i64, &-2), -4);
+        assert_eq!(SignedInt::div_floor(&-7i64, &-2), 3);
+        assert_eq!(SignedInt::div_floor(&-8i64, &2), -4);
+    }
+
+    #[test]
+    fn can_test_parity() {
+        assert!(UnsignedInt::is_even(10u16));
+        assert!(!UnsignedInt::is_even(7usize));
+        assert!(UnsignedInt::is_zero(0u8));
+    }
+}
@@ -1,2 +1,21 @@
+pub mod big_int;
+pub mod big_uint;
+pub mod combinatorics;
+pub mod digits;
+pub mod divisors;
+pub mod factor;
+pub mod fibonacci;
+pub mod figurate;
+pub mod integer;
+pub mod linear_recurrence;
+pub mod matrix;
+pub mod mod_int;
+pub mod modular;
+pub mod multiplicative_sieve;
+pub mod palindrome;
+pub mod primality;
+pub mod prime_count;
 pub mod prime_generator;
+pub mod ratio;
+pub mod segmented_sieve;
 pub mod triangular_numbers;
@@ -0,0 +1,337 @@
+use crate::integer::Semiring;
+use crate::matrix::Matrix;
+use std::collections::VecDeque;
+use std::ops::{Div, Sub};
+
+// a(n) = c_1 a(n - 1) + c_2 a(n - 2) + ... + c_k a(n - k) for n >= k, with a(0), ..., a(k - 1)
+// given. Over `ModInt` or `DynModInt` every term is a residue; over `BigUint` or `BigInt` it
+// is exact.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct LinearRecurrence<T: Semiring> {
+    coefficients: Vec<T>,
+    initial: Vec<T>,
+}
+
+impl<T: Semiring> LinearRecurrence<T> {
+    pub fn new(coefficients: Vec<T>, initial: Vec<T>) -> Self {
+        assert!(
+            !coefficients.is_empty(),
+            "Recurrence must have order at least 1"
+        );
+        assert_eq!(
+            coefficients.len(),
+            initial.len(),
+            "Need exactly one initial term per coefficient"
+        );
+
+        LinearRecurrence {
+            coefficients,
+            initial,
+        }
+    }
+
+    pub fn order(&self) -> usize {
+        self.coefficients.len()
+    }
+
+    pub fn coefficients(&self) -> &[T] {
+        &self.coefficients
+    }
+
+    pub fn initial_terms(&self) -> &[T] {
+        &self.initial
+    }
+
+    pub fn iter(&self) -> LinearRecurrenceTerms<'_, T> {
+        LinearRecurrenceTerms {
+            coefficients: &self.coefficients,
+            window: self.initial.iter().cloned().collect(),
+        }
+    }
+
+    // Kitamasa's method: a(n) = sum r_i a(i), where r(x) = x^n mod the characteristic
+    // polynomial x^k - c_1 x^(k - 1) - ... - c_k. Powering x by squaring with a reduction after
+    // each O(k^2) product gives O(k^2 log n), against O(k^3 log n) for the companion matrix,
+    // and needs no subtraction, so unsigned types work.
+    pub fn nth(&self, n: u64) -> T {
+        let k = self.order();
+        if n < k as u64 {
+            return self.initial[n as usize].clone();
+        }
+
+        let sample = &self.coefficients[0];
+        let mut x = vec![sample.zero_like(); k.max(2)];
+        x[1] = sample.one_like();
+        let mut base = self.reduce(x);
+        let mut result = vec![sample.zero_like(); k];
+        result[0] = sample.one_like();
+        let mut exponent = n;
+
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = self.mul_mod_characteristic(&result, &base);
+            }
+            exponent >>= 1;
+            if exponent > 0 {
+                base = self.mul_mod_characteristic(&base, &base);
+            }
+        }
+
+        result
+            .into_iter()
+            .zip(self.initial.iter().cloned())
+            .map(|(r, a)| r * a)
+            .reduce(|acc, term| acc + term)
+            .expect("Recurrence has order at least 1")
+    }
+
+    fn mul_mod_characteristic(&self, a: &[T], b: &[T]) -> Vec<T> {
+        let mut product = vec![a[0].zero_like(); a.len() + b.len() - 1];
+        for (i, x) in a.iter().enumerate() {
+            for (j, y) in b.iter().enumerate() {
+                product[i + j] = product[i + j].clone() + x.clone() * y.clone();
+            }
+        }
+        self.reduce(product)
+    }
+
+    // Rewrites x^d as sum c_j x^(d - j) from the top down until the degree is below k.
+    fn reduce(&self, mut polynomial: Vec<T>) -> Vec<T> {
+        let k = self.order();
+        for d in (k..polynomial.len()).rev() {
+            let top = polynomial[d].clone();
+            for (j, c) in self.coefficients.iter().enumerate() {
+                let target = d - j - 1;
+                polynomial[target] = polynomial[target].clone() + top.clone() * c.clone();
+            }
+        }
+        polynomial.truncate(k);
+        polynomial
+    }
+
+    // The k x k matrix taking (a(n + k - 1), ..., a(n)) to (a(n + k), ..., a(n + 1)).
+    pub fn companion_matrix(&self) -> Matrix<T> {
+        let k = self.order();
+        let sample = &self.coefficients[0];
+        let mut matrix = Matrix::identity_like(k, sample);
+        for i in 0..k {
+            matrix[(0, i)] = self.coefficients[i].clone();
+            if i > 0 {
+                matrix[(i, i)] = sample.zero_like();
+                matrix[(i, i - 1)] = sample.one_like();
+            }
+        }
+        matrix
+    }
+}
+
+pub struct LinearRecurrenceTerms<'a, T: Semiring> {
+    coefficients: &'a [T],
+    window: VecDeque<T>,
+}
+
+impl<T: Semiring> Iterator for LinearRecurrenceTerms<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let next = self
+            .coefficients
+            .iter()
+            .zip(self.window.iter().rev())
+            .map(|(c, a)| c.clone() * a.clone())
+            .reduce(|acc, term| acc + term)
+            .expect("Recurrence has order at least 1");
+
+        self.window.push_back(next);
+        self.window.pop_front()
+    }
+}
+
+// Berlekamp-Massey: the shortest linear recurrence that generates `terms`, which needs a
+// field such as `ModInt` with a prime modulus or `Ratio`. A recurrence of order L is only
+// pinned down by at least 2L terms. An all-zero sequence gives a(n) = 0 a(n - 1).
+pub fn berlekamp_massey<T>(terms: &[T]) -> LinearRecurrence<T>
+where
+    T: Semiring + Sub<Output = T> + Div<Output = T> + PartialEq,
+{
+    let sample = terms.first().expect("Need at least one term");
+    let (zero, one) = (sample.zero_like(), sample.one_like());
+
+    // connection is 1 + C_1 x + ... + C_L x^L, with sum C_i a(n - i) = -a(n).
+    let mut connection = vec![one.clone()];
+    let mut previous = vec![one.clone()];
+    let mut length = 0;
+    let mut shift = 1;
+    let mut previous_discrepancy = one;
+
+    for n in 0..terms.len() {
+        let discrepancy = (1..=length)
+            .map(|i| connection[i].clone() * terms[n - i].clone())
+            .fold(terms[n].clone(), |acc, term| acc + term);
+
+        if discrepancy == zero {
+            shift += 1;
+            continue;
+        }
+
+        let scale = discrepancy.clone() / previous_discrepancy.clone();
+        let mut updated = connection.clone();
+        if updated.len() < previous.len() + shift {
+            updated.resize(previous.len() + shift, zero.clone());
+        }
+        for (i, b) in previous.iter().enumerate() {
+            updated[i + shift] = updated[i + shift].clone() - scale.clone() * b.clone();
+        }
+
+        if 2 * length <= n {
+            previous = std::mem::replace(&mut connection, updated);
+            length = n + 1 - length;
+            previous_discrepancy = discrepancy;
+            shift = 1;
+        } else {
+            connection = updated;
+            shift += 1;
+        }
+    }
+
+    if length == 0 {
+        return LinearRecurrence::new(vec![zero], vec![terms[0].clone()]);
+    }
+
+    connection.resize(length + 1, zero.clone());
+    let coefficients = connection[1..]
+        .iter()
+        .map(|c| zero.clone() - c.clone())
+        .collect();
+
+    LinearRecurrence::new(coefficients, terms[..length].to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::big_uint::BigUint;
+    use crate::fibonacci::{fibonacci, fibonacci_big};
+    use crate::mod_int::{DynModInt, ModInt, Modulus};
+    use crate::ratio::Ratio;
+
+    type Mod = ModInt<1_000_000_007>;
+
+    fn tribonacci() -> LinearRecurrence<u64> {
+        LinearRecurrence::new(vec![1, 1, 1], vec![0, 0, 1])
+    }
+
+    #[test]
+    fn iterates_terms() {
+        assert_eq!(
+            tribonacci().iter().take(10).collect::<Vec<u64>>(),
+            vec![0, 0, 1, 1, 2, 4, 7, 13, 24, 44]
+        );
+        assert_eq!(
+            LinearRecurrence::new(vec![2u32], vec![3])
+                .iter()
+                .take(4)
+                .collect::<Vec<u32>>(),
+            vec![3, 6, 12, 24]
+        );
+    }
+
+    #[test]
+    fn nth_matches_iteration() {
+        let recurrences = [
+            tribonacci(),
+            LinearRecurrence::new(vec![4, 1], vec![0, 2]),
+            LinearRecurrence::new(vec![3], vec![1]),
+            LinearRecurrence::new(vec![0, 2, 0, 1], vec![1, 5, 0, 3]),
+        ];
+
+        recurrences.iter().for_each(|recurrence| {
+            recurrence
+                .iter()
+                .take(30)
+                .enumerate()
+                .for_each(|(n, term)| {
+                    assert_eq!(recurrence.nth(n as u64), term, "{:?}", recurrence)
+                });
+        });
+    }
+
+    #[test]
+    fn nth_handles_huge_indices_mod_m_and_exactly() {
+        let fibonacci_mod = LinearRecurrence::new(
+            vec![Mod::new(1), Mod::new(1)],
+            vec![Mod::new(0), Mod::new(1)],
+        );
+        assert_eq!(
+            fibonacci_mod.nth(1_000_000_000_000_000_000).value(),
+            crate::fibonacci::fibonacci_mod(1_000_000_000_000_000_000, 1_000_000_007)
+        );
+
+        let modulus = Modulus::new(1 << 40);
+        let lift = |x: u64| DynModInt::new(x, modulus);
+        let tribonacci_mod =
+            LinearRecurrence::new(vec![lift(1); 3], vec![lift(0), lift(0), lift(1)]);
+        assert_eq!(
+            tribonacci_mod.nth(60).value(),
+            tribonacci().nth(60) % (1 << 40)
+        );
+
+        let fibonacci_exact = LinearRecurrence::new(
+            vec![BigUint::one(), BigUint::one()],
+            vec![BigUint::zero(), BigUint::one()],
+        );
+        assert_eq!(fibonacci_exact.nth(1000), fibonacci_big(1000));
+    }
+
+    #[test]
+    fn companion_matrix_agrees_with_nth() {
+        let recurrence = tribonacci();
+        let state = recurrence.companion_matrix().pow(40).mul_vector(&[1, 0, 0]);
+        assert_eq!(
+            state,
+            vec![recurrence.nth(42), recurrence.nth(41), recurrence.nth(40)]
+        );
+        assert_eq!(
+            recurrence.companion_matrix(),
+            Matrix::from_rows(vec![vec![1, 1, 1], vec![1, 0, 0], vec![0, 1, 0]])
+        );
+    }
+
+    #[test]
+    fn berlekamp_massey_recovers_recurrences_mod_p() {
+        let lift = |terms: &[u64]| terms.iter().map(|&x| Mod::new(x)).collect::<Vec<Mod>>();
+
+        let fibonaccis = (0..20)
+            .map(|n| fibonacci::<u64>(n).unwrap())
+            .collect::<Vec<u64>>();
+        let found = berlekamp_massey(&lift(&fibonaccis));
+        assert_eq!(found.coefficients(), lift(&[1, 1]).as_slice());
+        assert_eq!(found.initial_terms(), lift(&[0, 1]).as_slice());
+
+        let hidden = LinearRecurrence::new(lift(&[3, 0, 5, 7]), lift(&[2, 7, 1, 8]));
+        let observed = hidden.iter().take(8).collect::<Vec<Mod>>();
+        assert_eq!(berlekamp_massey(&observed), hidden);
+
+        let zeros = berlekamp_massey(&lift(&[0, 0, 0]));
+        assert_eq!(zeros.order(), 1);
+        assert_eq!(zeros.nth(100).value(), 0);
+    }
+
+    #[test]
+    fn berlekamp_massey_works_over_rationals() {
+        let squares = (0..10i64)
+            .map(|n| Ratio::from_integer(n * n))
+            .collect::<Vec<Ratio<i64>>>();
+        let found = berlekamp_massey(&squares);
+
+        assert_eq!(
+            found.coefficients(),
+            &[
+                Ratio::from_integer(3),
+                Ratio::from_integer(-3),
+                Ratio::from_integer(1)
+            ]
+        );
+        assert_eq!(found.nth(1000), Ratio::from_integer(1_000_000));
+    }
+}
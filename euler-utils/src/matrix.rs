@@ -0,0 +1,201 @@
+use crate::integer::Semiring;
+use std::ops::{Index, IndexMut, Mul};
+
+// A dense row-major matrix over any semiring: primitives, `BigUint` for exact answers, or
+// `ModInt` and `DynModInt` when only a residue is wanted. Matrices are never empty, so there
+// is always an entry to take the identities from.
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct Matrix<T: Semiring> {
+    rows: usize,
+    cols: usize,
+    entries: Vec<T>,
+}
+
+impl<T: Semiring> Matrix<T> {
+    pub fn new(rows: usize, cols: usize, entries: Vec<T>) -> Self {
+        assert!(rows > 0 && cols > 0, "Matrix must not be empty");
+        assert_eq!(
+            entries.len(),
+            rows * cols,
+            "Entry count does not match shape"
+        );
+
+        Matrix {
+            rows,
+            cols,
+            entries,
+        }
+    }
+
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Self {
+        let cols = rows.first().map_or(0, Vec::len);
+        assert!(
+            rows.iter().all(|row| row.len() == cols),
+            "Rows have different lengths"
+        );
+
+        Matrix::new(rows.len(), cols, rows.into_iter().flatten().collect())
+    }
+
+    pub fn identity_like(size: usize, sample: &T) -> Self {
+        let entries = (0..size * size)
+            .map(|i| {
+                if i / size == i % size {
+                    sample.one_like()
+                } else {
+                    sample.zero_like()
+                }
+            })
+            .collect();
+
+        Matrix::new(size, size, entries)
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn is_square(&self) -> bool {
+        self.rows == self.cols
+    }
+
+    pub fn row(&self, i: usize) -> &[T] {
+        &self.entries[i * self.cols..(i + 1) * self.cols]
+    }
+
+    pub fn mul_vector(&self, vector: &[T]) -> Vec<T> {
+        assert_eq!(vector.len(), self.cols, "Vector length does not match");
+
+        (0..self.rows)
+            .map(|i| dot(self.row(i).iter().cloned(), vector.iter().cloned()))
+            .collect()
+    }
+
+    // Binary exponentiation, so O(k^3 log n) for a k x k matrix.
+    pub fn pow(&self, exponent: u64) -> Self {
+        assert!(self.is_square(), "Only square matrices have powers");
+
+        let mut result = Matrix::identity_like(self.rows, &self.entries[0]);
+        let mut base = self.clone();
+        let mut exponent = exponent;
+
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = &result * &base;
+            }
+            exponent >>= 1;
+            if exponent > 0 {
+                base = &base * &base;
+            }
+        }
+
+        result
+    }
+}
+
+fn dot<T: Semiring>(a: impl Iterator<Item = T>, b: impl Iterator<Item = T>) -> T {
+    a.zip(b)
+        .map(|(x, y)| x * y)
+        .reduce(|acc, xy| acc + xy)
+        .expect("Matrices are never empty")
+}
+
+impl<T: Semiring> Index<(usize, usize)> for Matrix<T> {
+    type Output = T;
+
+    fn index(&self, (i, j): (usize, usize)) -> &T {
+        assert!(i < self.rows && j < self.cols, "Matrix index out of range");
+        &self.entries[i * self.cols + j]
+    }
+}
+
+impl<T: Semiring> IndexMut<(usize, usize)> for Matrix<T> {
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut T {
+        assert!(i < self.rows && j < self.cols, "Matrix index out of range");
+        &mut self.entries[i * self.cols + j]
+    }
+}
+
+impl<T: Semiring> Mul<&Matrix<T>> for &Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn mul(self, other: &Matrix<T>) -> Matrix<T> {
+        assert_eq!(self.cols, other.rows, "Matrix shapes do not match");
+
+        let entries = (0..self.rows)
+            .flat_map(|i| {
+                (0..other.cols).map(move |j| {
+                    dot(
+                        self.row(i).iter().cloned(),
+                        (0..other.rows).map(|k| other[(k, j)].clone()),
+                    )
+                })
+            })
+            .collect();
+
+        Matrix::new(self.rows, other.cols, entries)
+    }
+}
+
+impl<T: Semiring> Mul for Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn mul(self, other: Matrix<T>) -> Matrix<T> {
+        &self * &other
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::big_uint::BigUint;
+    use crate::fibonacci::{fibonacci_big, fibonacci_mod};
+    use crate::mod_int::ModInt;
+
+    #[test]
+    fn can_multiply_matrices() {
+        let a = Matrix::from_rows(vec![vec![1i64, 2, 3], vec![4, 5, 6]]);
+        let b = Matrix::from_rows(vec![vec![7i64, 8], vec![9, 10], vec![11, 12]]);
+
+        assert_eq!(
+            &a * &b,
+            Matrix::from_rows(vec![vec![58, 64], vec![139, 154]])
+        );
+        assert_eq!(a.mul_vector(&[1, 0, -1]), vec![-2, -2]);
+        assert_eq!(a[(1, 2)], 6);
+    }
+
+    #[test]
+    #[should_panic(expected = "shapes do not match")]
+    fn mismatched_shapes_panic() {
+        let a = Matrix::from_rows(vec![vec![1u32, 2]]);
+        let _ = &a * &a;
+    }
+
+    #[test]
+    fn powers_of_the_fibonacci_matrix() {
+        let q = Matrix::from_rows(vec![vec![1u64, 1], vec![1, 0]]);
+        assert_eq!(q.pow(0), Matrix::identity_like(2, &0));
+        assert_eq!(q.pow(10)[(0, 1)], 55);
+        assert_eq!(q.pow(92)[(0, 1)], 7_540_113_804_746_346_429);
+
+        let q_mod = Matrix::from_rows(vec![
+            vec![ModInt::<1_000_000_007>::new(1), ModInt::new(1)],
+            vec![ModInt::new(1), ModInt::new(0)],
+        ]);
+        assert_eq!(
+            q_mod.pow(1_000_000_000_000)[(0, 1)].value(),
+            fibonacci_mod(1_000_000_000_000, 1_000_000_007)
+        );
+
+        let q_big = Matrix::from_rows(vec![
+            vec![BigUint::one(), BigUint::one()],
+            vec![BigUint::one(), BigUint::zero()],
+        ]);
+        assert_eq!(q_big.pow(300)[(0, 1)], fibonacci_big(300));
+    }
+}
@@ -0,0 +1,586 @@
+use crate::integer::Semiring;
+use crate::modular::mod_inverse;
+use std::fmt;
+use std::iter::{Product, Sum};
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+// Arithmetic mod m on raw residues. Odd moduli keep residues in Montgomery form, x R mod m
+// with R = 2^64, so a product costs two 64 x 64 multiplications and no division. Even moduli
+// (10^10 for last-digit problems, say) have no Montgomery form and use Barrett reduction on
+// plain residues instead, which also avoids the slow u128 `%`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Modulus {
+    m: u64,
+    // m * m_inv = 1 (mod 2^64), only meaningful for odd m.
+    m_inv: u64,
+    // R^2 mod m, which takes a plain value into Montgomery form with one reduction.
+    r_squared: u64,
+    // floor((2^128 - 1) / m), only meaningful for even m.
+    barrett_factor: u128,
+}
+
+impl Modulus {
+    pub const fn new(m: u64) -> Self {
+        assert!(m > 0, "Modulus must be positive");
+
+        if m.is_multiple_of(2) {
+            return Modulus {
+                m,
+                m_inv: 0,
+                r_squared: 0,
+                barrett_factor: u128::MAX / m as u128,
+            };
+        }
+
+        // Newton's iteration doubles the number of correct low bits each step, and m itself
+        // is already an inverse mod 2^3.
+        let mut m_inv = m;
+        let mut i = 0;
+        while i < 5 {
+            m_inv = m_inv.wrapping_mul(2u64.wrapping_sub(m.wrapping_mul(m_inv)));
+            i += 1;
+        }
+
+        let r = (1u128 << 64) % m as u128;
+        Modulus {
+            m,
+            m_inv,
+            r_squared: (r * r % m as u128) as u64,
+            barrett_factor: 0,
+        }
+    }
+
+    pub fn modulus(&self) -> u64 {
+        self.m
+    }
+
+    fn is_montgomery(&self) -> bool {
+        self.m % 2 == 1
+    }
+
+    // Montgomery reduction: t R^-1 mod m for t < m R. The low halves of t and k m agree, so
+    // their difference divided by R is just the difference of the high halves.
+    fn reduce(&self, t: u128) -> u64 {
+        let k = (t as u64).wrapping_mul(self.m_inv);
+        let t_high = (t >> 64) as u64;
+        let km_high = ((k as u128 * self.m as u128) >> 64) as u64;
+
+        if t_high >= km_high {
+            t_high - km_high
+        } else {
+            t_high.wrapping_sub(km_high).wrapping_add(self.m)
+        }
+    }
+
+    // Barrett reduction: t mod m for t < 2^128. The quotient estimate floor(t f / 2^128) with
+    // f = floor((2^128 - 1) / m) is at most two below the true quotient, so at most two
+    // subtractions of m remain.
+    fn barrett_reduce(&self, t: u128) -> u64 {
+        let quotient = mul_high(t, self.barrett_factor);
+        let mut remainder = t.wrapping_sub(quotient.wrapping_mul(self.m as u128));
+        while remainder >= self.m as u128 {
+            remainder -= self.m as u128;
+        }
+        remainder as u64
+    }
+
+    pub fn to_residue(&self, x: u64) -> u64 {
+        if self.is_montgomery() {
+            self.reduce((x % self.m) as u128 * self.r_squared as u128)
+        } else {
+            x % self.m
+        }
+    }
+
+    pub fn from_residue(&self, residue: u64) -> u64 {
+        if self.is_montgomery() {
+            self.reduce(residue as u128)
+        } else {
+            residue
+        }
+    }
+
+    pub fn add(&self, a: u64, b: u64) -> u64 {
+        let (sum, overflowed) = a.overflowing_add(b);
+        if overflowed || sum >= self.m {
+            sum.wrapping_sub(self.m)
+        } else {
+            sum
+        }
+    }
+
+    pub fn sub(&self, a: u64, b: u64) -> u64 {
+        if a >= b {
+            a - b
+        } else {
+            a.wrapping_sub(b).wrapping_add(self.m)
+        }
+    }
+
+    pub fn mul(&self, a: u64, b: u64) -> u64 {
+        if self.is_montgomery() {
+            self.reduce(a as u128 * b as u128)
+        } else {
+            self.barrett_reduce(a as u128 * b as u128)
+        }
+    }
+
+    pub fn pow(&self, base: u64, exponent: u64) -> u64 {
+        let mut result = self.to_residue(1);
+        let mut base = base;
+        let mut exponent = exponent;
+
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = self.mul(result, base);
+            }
+            base = self.mul(base, base);
+            exponent >>= 1;
+        }
+
+        result
+    }
+}
+
+// The high 128 bits of the 256-bit product a b, from four 64 x 64 products.
+const fn mul_high(a: u128, b: u128) -> u128 {
+    let (a_low, a_high) = (a as u64 as u128, a >> 64);
+    let (b_low, b_high) = (b as u64 as u128, b >> 64);
+
+    let low_low = a_low * b_low;
+    let low_high = a_low * b_high;
+    let high_low = a_high * b_low;
+    let high_high = a_high * b_high;
+
+    let middle = (low_low >> 64) + (low_high as u64 as u128) + (high_low as u64 as u128);
+    high_high + (low_high >> 64) + (high_low >> 64) + (middle >> 64)
+}
+
+// An element of Z / MZ with the modulus fixed at compile time.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ModInt<const M: u64> {
+    residue: u64,
+}
+
+impl<const M: u64> ModInt<M> {
+    const MODULUS: Modulus = Modulus::new(M);
+
+    pub fn new(x: u64) -> Self {
+        ModInt {
+            residue: Self::MODULUS.to_residue(x),
+        }
+    }
+
+    pub fn zero() -> Self {
+        ModInt { residue: 0 }
+    }
+
+    pub fn one() -> Self {
+        ModInt::new(1)
+    }
+
+    pub fn modulus() -> u64 {
+        M
+    }
+
+    pub fn value(&self) -> u64 {
+        Self::MODULUS.from_residue(self.residue)
+    }
+
+    pub fn pow(&self, exponent: u64) -> Self {
+        self.with_residue(Self::MODULUS.pow(self.residue, exponent))
+    }
+
+    pub fn inv(&self) -> Option<Self> {
+        mod_inverse(self.value(), M).map(ModInt::new)
+    }
+
+    fn arithmetic(&self) -> &Modulus {
+        &Self::MODULUS
+    }
+
+    fn with_residue(&self, residue: u64) -> Self {
+        ModInt { residue }
+    }
+}
+
+impl<const M: u64> From<u64> for ModInt<M> {
+    fn from(x: u64) -> Self {
+        ModInt::new(x)
+    }
+}
+
+impl<const M: u64> Semiring for ModInt<M> {
+    fn zero_like(&self) -> Self {
+        ModInt::zero()
+    }
+
+    fn one_like(&self) -> Self {
+        ModInt::one()
+    }
+}
+
+// An element of Z / mZ with the modulus chosen at runtime.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DynModInt {
+    residue: u64,
+    modulus: Modulus,
+}
+
+impl DynModInt {
+    pub fn new(x: u64, modulus: Modulus) -> Self {
+        DynModInt {
+            residue: modulus.to_residue(x),
+            modulus,
+        }
+    }
+
+    pub fn zero(modulus: Modulus) -> Self {
+        DynModInt {
+            residue: 0,
+            modulus,
+        }
+    }
+
+    pub fn one(modulus: Modulus) -> Self {
+        DynModInt::new(1, modulus)
+    }
+
+    pub fn modulus(&self) -> u64 {
+        self.modulus.modulus()
+    }
+
+    pub fn value(&self) -> u64 {
+        self.modulus.from_residue(self.residue)
+    }
+
+    pub fn pow(&self, exponent: u64) -> Self {
+        self.with_residue(self.modulus.pow(self.residue, exponent))
+    }
+
+    pub fn inv(&self) -> Option<Self> {
+        mod_inverse(self.value(), self.modulus()).map(|x| DynModInt::new(x, self.modulus))
+    }
+
+    fn arithmetic(&self) -> &Modulus {
+        &self.modulus
+    }
+
+    fn with_residue(&self, residue: u64) -> Self {
+        DynModInt {
+            residue,
+            modulus: self.modulus,
+        }
+    }
+}
+
+impl Semiring for DynModInt {
+    fn zero_like(&self) -> Self {
+        DynModInt::zero(self.modulus)
+    }
+
+    fn one_like(&self) -> Self {
+        DynModInt::one(self.modulus)
+    }
+}
+
+macro_rules! impl_mod_int_ops {
+    ([$($generics:tt)*] $t:ty) => {
+        impl<$($generics)*> $t {
+            fn check_same_modulus(&self, other: &Self) {
+                debug_assert_eq!(
+                    self.arithmetic().modulus(),
+                    other.arithmetic().modulus(),
+                    "Mixed moduli"
+                );
+            }
+        }
+
+        impl<$($generics)*> Add for $t {
+            type Output = Self;
+
+            fn add(self, other: Self) -> Self {
+                self.check_same_modulus(&other);
+                self.with_residue(self.arithmetic().add(self.residue, other.residue))
+            }
+        }
+
+        impl<$($generics)*> Sub for $t {
+            type Output = Self;
+
+            fn sub(self, other: Self) -> Self {
+                self.check_same_modulus(&other);
+                self.with_residue(self.arithmetic().sub(self.residue, other.residue))
+            }
+        }
+
+        impl<$($generics)*> Mul for $t {
+            type Output = Self;
+
+            fn mul(self, other: Self) -> Self {
+                self.check_same_modulus(&other);
+                self.with_residue(self.arithmetic().mul(self.residue, other.residue))
+            }
+        }
+
+        impl<$($generics)*> Div for $t {
+            type Output = Self;
+
+            fn div(self, other: Self) -> Self {
+                Mul::mul(self, other.inv().expect("Divisor is not invertible"))
+            }
+        }
+
+        impl<$($generics)*> Neg for $t {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                self.with_residue(self.arithmetic().sub(0, self.residue))
+            }
+        }
+
+        impl<$($generics)*> AddAssign for $t {
+            fn add_assign(&mut self, other: Self) {
+                *self = *self + other;
+            }
+        }
+
+        impl<$($generics)*> SubAssign for $t {
+            fn sub_assign(&mut self, other: Self) {
+                *self = *self - other;
+            }
+        }
+
+        impl<$($generics)*> MulAssign for $t {
+            fn mul_assign(&mut self, other: Self) {
+                *self = *self * other;
+            }
+        }
+
+        impl<$($generics)*> DivAssign for $t {
+            fn div_assign(&mut self, other: Self) {
+                *self = *self / other;
+            }
+        }
+
+        impl<$($generics)*> fmt::Display for $t {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                fmt::Display::fmt(&self.value(), f)
+            }
+        }
+
+        impl<$($generics)*> fmt::Debug for $t {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "{} (mod {})", self.value(), self.arithmetic().modulus())
+            }
+        }
+    };
+}
+
+impl_mod_int_ops!([const M: u64] ModInt<M>);
+impl_mod_int_ops!([] DynModInt);
+
+impl<const M: u64> Sum for ModInt<M> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(ModInt::zero(), |acc, x| acc + x)
+    }
+}
+
+impl<const M: u64> Product for ModInt<M> {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(ModInt::one(), |acc, x| acc * x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::big_uint::BigUint;
+
+    type Mod7 = ModInt<7>;
+    type ModPrime = ModInt<1_000_000_007>;
+    type ModTen10 = ModInt<10_000_000_000>;
+
+    const LARGEST_U64_PRIME: u64 = 18_446_744_073_709_551_557;
+
+    #[test]
+    fn residues_round_trip() {
+        [
+            1,
+            2,
+            7,
+            10,
+            1_000_000_007,
+            10_000_000_000,
+            LARGEST_U64_PRIME,
+            u64::MAX,
+        ]
+        .iter()
+        .for_each(|&m| {
+            let modulus = Modulus::new(m);
+            [0, 1, 2, m / 2, m - 1, u64::MAX].iter().for_each(|&x| {
+                assert_eq!(
+                    modulus.from_residue(modulus.to_residue(x)),
+                    x % m,
+                    "{} mod {}",
+                    x,
+                    m
+                );
+            });
+        });
+    }
+
+    #[test]
+    fn arithmetic_matches_u128() {
+        [
+            3,
+            1_000_000_007,
+            10_000_000_000,
+            LARGEST_U64_PRIME,
+            u64::MAX,
+        ]
+        .iter()
+        .for_each(|&m| {
+            let modulus = Modulus::new(m);
+            let values = [0, 1, m / 3, m / 2 + 1, m - 1];
+            values.iter().for_each(|&a| {
+                values.iter().for_each(|&b| {
+                    let (x, y) = (DynModInt::new(a, modulus), DynModInt::new(b, modulus));
+                    let (a, b, m) = (a as u128, b as u128, m as u128);
+                    assert_eq!((x + y).value() as u128, (a + b) % m);
+                    assert_eq!((x - y).value() as u128, (a + m - b) % m);
+                    assert_eq!((x * y).value() as u128, a * b % m);
+                });
+            });
+        });
+    }
+
+    #[test]
+    fn can_raise_to_powers() {
+        assert_eq!(Mod7::new(3).pow(6), Mod7::one());
+        assert_eq!(ModPrime::new(2).pow(1_000_000_006), ModPrime::one());
+        assert_eq!(ModInt::<1000>::new(2).pow(10).value(), 24);
+        assert_eq!(ModInt::<1>::new(5).pow(0).value(), 0);
+
+        let p = Modulus::new(LARGEST_U64_PRIME);
+        assert_eq!(
+            DynModInt::new(3, p).pow(LARGEST_U64_PRIME - 1),
+            DynModInt::one(p)
+        );
+    }
+
+    #[test]
+    fn can_invert_and_divide() {
+        assert_eq!(Mod7::new(3).inv(), Some(Mod7::new(5)));
+        assert_eq!(ModTen10::new(2).inv(), None);
+        assert_eq!(
+            ModTen10::new(3)
+                .inv()
+                .map(|x| (x * ModTen10::new(3)).value()),
+            Some(1)
+        );
+        assert_eq!(
+            ModPrime::new(10) / ModPrime::new(4),
+            ModPrime::new(5) / ModPrime::new(2)
+        );
+        assert_eq!(-Mod7::new(3), Mod7::new(4));
+        assert_eq!(-Mod7::zero(), Mod7::zero());
+    }
+
+    #[test]
+    #[should_panic(expected = "not invertible")]
+    fn dividing_by_a_non_unit_panics() {
+        let _ = ModTen10::new(1) / ModTen10::new(5);
+    }
+
+    #[test]
+    fn assign_operators_update_in_place() {
+        let mut x = Mod7::new(5);
+        x += Mod7::new(4);
+        x *= Mod7::new(3);
+        x -= Mod7::new(1);
+        x /= Mod7::new(5);
+        assert_eq!(x.value(), 1);
+        assert_eq!(format!("{}", x), "1");
+        assert_eq!(format!("{:?}", x), "1 (mod 7)");
+    }
+
+    #[test]
+    fn can_find_last_ten_digits_of_self_powers_for_problem_48() {
+        let sum = (1..=1000u64)
+            .map(|n| ModTen10::new(n).pow(n))
+            .sum::<ModTen10>();
+        assert_eq!(sum.value(), 9_110_846_700);
+
+        let modulus = Modulus::new(10_000_000_000);
+        let small = (1..=10u64).fold(DynModInt::zero(modulus), |acc, n| {
+            acc + DynModInt::new(n, modulus).pow(n)
+        });
+        assert_eq!(small.value(), 405_071_317);
+    }
+
+    #[test]
+    fn barrett_reduction_matches_u128_for_even_moduli() {
+        let mut x = 0x9E37_79B9_7F4A_7C15u64;
+        let mut next = || {
+            x = x
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            x
+        };
+
+        [
+            2,
+            1 << 32,
+            1 << 63,
+            10_000_000_000,
+            1_000_000_000_000_000_000,
+            u64::MAX - 1,
+        ]
+        .iter()
+        .for_each(|&m| {
+            let modulus = Modulus::new(m);
+            (0..1000).for_each(|_| {
+                let (a, b) = (next() % m, next() % m);
+                assert_eq!(
+                    modulus.mul(a, b) as u128,
+                    a as u128 * b as u128 % m as u128,
+                    "{} * {} mod {}",
+                    a,
+                    b,
+                    m
+                );
+            });
+            assert_eq!(modulus.mul(m - 1, m - 1), 1);
+        });
+    }
+
+    #[test]
+    fn self_powers_mod_ten_to_the_ten_match_exact_arithmetic() {
+        let ten_to_the_ten = BigUint::from(10_000_000_000u64);
+        let exact = (1..=1000u32)
+            .map(|n| BigUint::from(n).pow(n))
+            .sum::<BigUint>();
+        let expected = (&exact % &ten_to_the_ten).to_u64().unwrap();
+
+        let sum = (1..=1000u64)
+            .map(|n| ModTen10::new(n).pow(n))
+            .sum::<ModTen10>();
+        assert_eq!(sum.value(), expected);
+
+        let modulus = Modulus::new(10_000_000_000);
+        let dynamic_sum = (1..=1000u64)
+            .map(|n| DynModInt::new(n, modulus).pow(n))
+            .fold(DynModInt::zero(modulus), |acc, x| acc + x);
+        assert_eq!(dynamic_sum.value(), expected);
+    }
+
+    #[test]
+    fn can_take_products() {
+        let factorial = (1..=20u64).map(ModPrime::new).product::<ModPrime>();
+        assert_eq!(
+            factorial.value(),
+            (2_432_902_008_176_640_000u128 % 1_000_000_007) as u64
+        );
+    }
+}
@@ -0,0 +1,218 @@
+use crate::integer::UnsignedInt;
+use crate::segmented_sieve::SegmentedSieve;
+
+// Stein's binary gcd: strip the shared powers of two once, then only subtract and shift.
+pub fn gcd<T: UnsignedInt>(a: T, b: T) -> T {
+    if a.is_zero() {
+        return b;
+    } else if b.is_zero() {
+        return a;
+    }
+
+    let shift = a.trailing_zeros().min(b.trailing_zeros());
+    let mut a = a >> a.trailing_zeros();
+    let mut b = b;
+
+    loop {
+        b = b >> b.trailing_zeros();
+        if a > b {
+            (a, b) = (b, a);
+        }
+        b -= a;
+        if b.is_zero() {
+            return a << shift;
+        }
+    }
+}
+
+// Returns None if the lcm does not fit in T.
+pub fn lcm<T: UnsignedInt>(a: T, b: T) -> Option<T> {
+    if a.is_zero() || b.is_zero() {
+        return Some(T::ZERO);
+    }
+
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+// lcm(1, 2, ..., n) is the product of the largest power of each prime p <= n that is still
+// at most n. Returns None once that passes u128::MAX, which first happens at n = 89.
+pub fn lcm_of_range(n: u64) -> Option<u128> {
+    SegmentedSieve::new(n as usize + 1)
+        .primes()
+        .into_iter()
+        .try_fold(1u128, |acc, p| {
+            let p = p as u64;
+            let mut power = p;
+            while let Some(next) = power.checked_mul(p).filter(|&next| next <= n) {
+                power = next;
+            }
+            acc.checked_mul(power as u128)
+        })
+}
+
+// Returns (g, x, y) with a x + b y = g = gcd(a, b), g >= 0.
+pub fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+pub fn mod_inverse(a: u64, m: u64) -> Option<u64> {
+    if m == 0 {
+        return None;
+    }
+
+    let (g, x, _) = extended_gcd(a as i128, m as i128);
+    (g == 1).then(|| x.rem_euclid(m as i128) as u64)
+}
+
+// Merges x = r1 (mod m1) and x = r2 (mod m2) into one congruence mod lcm(m1, m2). The moduli
+// need not be coprime, only the residues consistent: r1 = r2 (mod gcd(m1, m2)).
+fn merge_congruences(first: (u64, u64), second: (u64, u64)) -> Option<(u64, u64)> {
+    let ((r1, m1), (r2, m2)) = (
+        (first.0 % first.1, first.1),
+        (second.0 % second.1, second.1),
+    );
+    let g = gcd(m1, m2);
+    let difference = (r2 as i128 - r1 as i128).rem_euclid(m2 as i128) as u128;
+    if !difference.is_multiple_of(g as u128) {
+        return None;
+    }
+
+    let (m1_reduced, m2_reduced) = (m1 / g, m2 / g);
+    let modulus = m1_reduced.checked_mul(m2)?;
+    let inverse = mod_inverse(m1_reduced % m2_reduced, m2_reduced)? as u128;
+    let k = (difference / g as u128) % m2_reduced as u128 * inverse % m2_reduced as u128;
+    let x = (r1 as u128 + m1 as u128 * k) % modulus as u128;
+
+    Some((x as u64, modulus))
+}
+
+// Solves a system of congruences x = r_i (mod m_i), returning the smallest non-negative
+// solution and the modulus it is unique under. None if the system has no solution, a
+// modulus is zero, or the combined modulus does not fit in a u64.
+pub fn crt(congruences: &[(u64, u64)]) -> Option<(u64, u64)> {
+    if congruences.iter().any(|&(_, m)| m == 0) {
+        return None;
+    }
+
+    congruences.iter().try_fold((0, 1), |acc, &congruence| {
+        merge_congruences(acc, congruence)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn euclid(a: u64, b: u64) -> u64 {
+        if b == 0 {
+            a
+        } else {
+            euclid(b, a % b)
+        }
+    }
+
+    #[test]
+    fn binary_gcd_matches_euclid() {
+        (0..200u64).for_each(|a| {
+            (0..200u64).for_each(|b| assert_eq!(gcd(a, b), euclid(a, b), "gcd({}, {})", a, b));
+        });
+        assert_eq!(gcd(u128::MAX, u128::MAX - 1), 1);
+        assert_eq!(gcd(1u128 << 100, 3u128 << 90), 1 << 90);
+        assert_eq!(gcd(48u8, 180), 12);
+    }
+
+    #[test]
+    fn can_find_lcm() {
+        assert_eq!(lcm(4u32, 6), Some(12));
+        assert_eq!(lcm(0u32, 6), Some(0));
+        assert_eq!(lcm(u64::MAX, u64::MAX - 1), None);
+        assert_eq!(lcm(1u64 << 63, 1 << 62), Some(1 << 63));
+    }
+
+    #[test]
+    fn can_find_lcm_of_ranges() {
+        assert_eq!(lcm_of_range(0), Some(1));
+        assert_eq!(lcm_of_range(1), Some(1));
+        assert_eq!(lcm_of_range(10), Some(2520));
+        assert_eq!(lcm_of_range(20), Some(232_792_560));
+
+        let folded = (1..=88u128).try_fold(1u128, lcm);
+        assert_eq!(lcm_of_range(88), folded);
+        assert_eq!(lcm_of_range(89), None);
+    }
+
+    #[test]
+    fn extended_gcd_gives_bezout_coefficients() {
+        [
+            (240, 46),
+            (46, 240),
+            (0, 5),
+            (5, 0),
+            (-12, 18),
+            (17, 13),
+            (0, 0),
+        ]
+        .iter()
+        .for_each(|&(a, b)| {
+            let (g, x, y) = extended_gcd(a, b);
+            assert_eq!(
+                g,
+                euclid(a.unsigned_abs() as u64, b.unsigned_abs() as u64) as i128
+            );
+            assert_eq!(a * x + b * y, g, "({}, {})", a, b);
+        });
+    }
+
+    #[test]
+    fn can_find_modular_inverses() {
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(10, 17), Some(12));
+        assert_eq!(mod_inverse(6, 9), None);
+        assert_eq!(mod_inverse(5, 1), Some(0));
+
+        let p = 18_446_744_073_709_551_557;
+        let inverse = mod_inverse(123_456_789, p).unwrap();
+        assert_eq!((inverse as u128 * 123_456_789) % p as u128, 1);
+    }
+
+    #[test]
+    fn can_solve_coprime_systems() {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt(&[]), Some((0, 1)));
+        assert_eq!(crt(&[(10, 7)]), Some((3, 7)));
+    }
+
+    #[test]
+    fn can_solve_non_coprime_systems() {
+        assert_eq!(crt(&[(2, 4), (4, 6)]), Some((10, 12)));
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+        assert_eq!(crt(&[(3, 6), (3, 6)]), Some((3, 6)));
+        assert_eq!(crt(&[(1, 0)]), None);
+    }
+
+    #[test]
+    fn crt_handles_large_moduli() {
+        let (p, q) = (4_294_967_291u64, 4_294_967_279u64);
+        let x = 12_345_678_901_234_567_890u64 % (p * q);
+        assert_eq!(crt(&[(x % p, p), (x % q, q)]), Some((x, p * q)));
+        assert_eq!(
+            crt(&[(1, u64::MAX), (0, u64::MAX - 1)]).map(|(_, m)| m),
+            None
+        );
+    }
+}
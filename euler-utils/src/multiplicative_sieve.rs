@@ -0,0 +1,247 @@
+use std::ops::Mul;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct MultiplicativeSieve {
+    limit: usize,
+    pub primes: Vec<usize>,
+    smallest_prime_factor: Vec<u32>,
+    // The full power of the smallest prime factor dividing n, so n / that is coprime to it.
+    smallest_prime_power: Vec<u32>,
+    phi: Vec<u32>,
+    mu: Vec<i8>,
+    tau: Vec<u32>,
+    sigma: Vec<u64>,
+}
+
+impl MultiplicativeSieve {
+    pub fn new(limit: usize) -> Self {
+        assert!(
+            limit < u32::MAX as usize,
+            "MultiplicativeSieve stores its tables as u32, so the limit must fit in a u32"
+        );
+
+        let size = limit + 1;
+        let mut sieve = MultiplicativeSieve {
+            limit,
+            primes: Vec::new(),
+            smallest_prime_factor: vec![0; size],
+            smallest_prime_power: vec![0; size],
+            phi: vec![0; size],
+            mu: vec![0; size],
+            tau: vec![0; size],
+            sigma: vec![0; size],
+        };
+
+        if limit >= 1 {
+            sieve.smallest_prime_factor[1] = 1;
+            sieve.smallest_prime_power[1] = 1;
+            sieve.phi[1] = 1;
+            sieve.mu[1] = 1;
+            sieve.tau[1] = 1;
+            sieve.sigma[1] = 1;
+        }
+
+        (2..size).for_each(|i| sieve.sieve_from(i));
+        sieve
+    }
+
+    // Linear sieve step: every composite j is reached exactly once, as spf(j) * (j / spf(j)).
+    fn sieve_from(&mut self, i: usize) {
+        if self.smallest_prime_factor[i] == 0 {
+            self.primes.push(i);
+            self.smallest_prime_factor[i] = i as u32;
+            self.smallest_prime_power[i] = i as u32;
+            self.phi[i] = i as u32 - 1;
+            self.mu[i] = -1;
+            self.tau[i] = 2;
+            self.sigma[i] = i as u64 + 1;
+        }
+
+        let spf_i = self.smallest_prime_factor[i] as usize;
+        for k in 0..self.primes.len() {
+            let p = self.primes[k];
+            if p > spf_i || i * p > self.limit {
+                break;
+            }
+
+            let j = i * p;
+            self.smallest_prime_factor[j] = p as u32;
+
+            if p == spf_i {
+                let power = self.smallest_prime_power[i] as usize;
+                let rest = i / power;
+                let next_power = power * p;
+
+                self.smallest_prime_power[j] = next_power as u32;
+                self.phi[j] = self.phi[rest] * self.phi[power] * p as u32;
+                self.mu[j] = 0;
+                self.tau[j] = self.tau[rest] * (self.tau[power] + 1);
+                self.sigma[j] = self.sigma[rest] * (self.sigma[power] * p as u64 + 1);
+            } else {
+                self.smallest_prime_power[j] = p as u32;
+                self.phi[j] = self.phi[i] * self.phi[p];
+                self.mu[j] = -self.mu[i];
+                self.tau[j] = self.tau[i] * 2;
+                self.sigma[j] = self.sigma[i] * self.sigma[p];
+            }
+        }
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    pub fn smallest_prime_factor(&self, n: usize) -> usize {
+        self.smallest_prime_factor[n] as usize
+    }
+
+    pub fn is_prime(&self, n: usize) -> bool {
+        n >= 2 && self.smallest_prime_factor(n) == n
+    }
+
+    pub fn phi(&self, n: usize) -> usize {
+        self.phi[n] as usize
+    }
+
+    pub fn mu(&self, n: usize) -> i8 {
+        self.mu[n]
+    }
+
+    pub fn tau(&self, n: usize) -> usize {
+        self.tau[n] as usize
+    }
+
+    pub fn sigma(&self, n: usize) -> u64 {
+        self.sigma[n]
+    }
+
+    pub fn factorize(&self, n: usize) -> Vec<(usize, u32)> {
+        let mut factors = Vec::new();
+        let mut n = n;
+
+        while n > 1 {
+            let p = self.smallest_prime_factor(n);
+            let power = self.smallest_prime_power[n] as usize;
+            factors.push((p, self.tau[power] - 1));
+            n /= power;
+        }
+
+        factors
+    }
+
+    pub fn multiplicative<T, F>(&self, at_prime_power: F) -> Vec<T>
+    where
+        T: Copy + Mul<Output = T> + From<u8>,
+        F: Fn(usize, u32) -> T,
+    {
+        let mut values = vec![T::from(0); self.limit + 1];
+        if self.limit >= 1 {
+            values[1] = T::from(1);
+        }
+
+        (2..=self.limit).for_each(|n| {
+            let p = self.smallest_prime_factor(n);
+            let power = self.smallest_prime_power[n] as usize;
+            let exponent = self.tau[power] - 1;
+            values[n] = values[n / power] * at_prime_power(p, exponent);
+        });
+
+        values
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gcd(a: usize, b: usize) -> usize {
+        if b == 0 {
+            a
+        } else {
+            gcd(b, a % b)
+        }
+    }
+
+    fn divisors(n: usize) -> Vec<usize> {
+        (1..=n).filter(|d| n.is_multiple_of(*d)).collect()
+    }
+
+    #[test]
+    fn can_make_empty_sieves() {
+        let s = MultiplicativeSieve::new(0);
+        assert_eq!(s.limit(), 0);
+        assert!(s.primes.is_empty());
+
+        let s = MultiplicativeSieve::new(1);
+        assert_eq!(s.phi(1), 1);
+        assert_eq!(s.mu(1), 1);
+        assert!(s.primes.is_empty());
+    }
+
+    #[test]
+    fn finds_primes_and_smallest_prime_factors() {
+        let s = MultiplicativeSieve::new(30);
+
+        assert_eq!(s.primes, vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+        assert_eq!(s.smallest_prime_factor(1), 1);
+        assert_eq!(s.smallest_prime_factor(15), 3);
+        assert_eq!(s.smallest_prime_factor(25), 5);
+        assert_eq!(s.smallest_prime_factor(29), 29);
+        assert!(s.is_prime(29));
+        assert!(!s.is_prime(1));
+        assert!(!s.is_prime(27));
+    }
+
+    #[test]
+    fn tables_match_brute_force() {
+        let s = MultiplicativeSieve::new(500);
+
+        (1..=500).for_each(|n| {
+            let ds = divisors(n);
+            let phi = (1..=n).filter(|&k| gcd(n, k) == 1).count();
+            let square_free = (2..=n).all(|k| !n.is_multiple_of(k * k));
+            let mu = match (square_free, s.factorize(n).len() % 2) {
+                (false, _) => 0,
+                (true, 0) => 1,
+                (true, _) => -1,
+            };
+
+            assert_eq!(s.tau(n), ds.len(), "tau({})", n);
+            assert_eq!(s.sigma(n), ds.iter().sum::<usize>() as u64, "sigma({})", n);
+            assert_eq!(s.phi(n), phi, "phi({})", n);
+            assert_eq!(s.mu(n), mu, "mu({})", n);
+        });
+    }
+
+    #[test]
+    fn can_factorize_from_the_table() {
+        let s = MultiplicativeSieve::new(1000);
+
+        assert_eq!(s.factorize(1), vec![]);
+        assert_eq!(s.factorize(360), vec![(2, 3), (3, 2), (5, 1)]);
+        assert_eq!(s.factorize(997), vec![(997, 1)]);
+        assert_eq!(s.factorize(512), vec![(2, 9)]);
+    }
+
+    #[test]
+    fn can_plug_in_a_custom_multiplicative_function() {
+        let s = MultiplicativeSieve::new(200);
+
+        let sigma_2 = s.multiplicative(|p, e| (0..=e).map(|k| (p as u64).pow(2 * k)).sum::<u64>());
+        let tau = s.multiplicative(|_, e| e as u64 + 1);
+
+        (1..=200).for_each(|n| {
+            let expected = divisors(n).iter().map(|&d| (d * d) as u64).sum::<u64>();
+            assert_eq!(sigma_2[n], expected);
+            assert_eq!(tau[n], s.tau(n) as u64);
+        });
+    }
+
+    #[test]
+    fn can_sum_totients_for_problem_72_example() {
+        let s = MultiplicativeSieve::new(8);
+        let reduced_proper_fractions = (2..=8).map(|d| s.phi(d)).sum::<usize>();
+
+        assert_eq!(reduced_proper_fractions, 21);
+    }
+}
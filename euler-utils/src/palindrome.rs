@@ -0,0 +1,270 @@
+use crate::big_uint::BigUint;
+use crate::digits::{from_digits_in_base, DecimalDigits, Digits};
+use crate::integer::UnsignedInt;
+
+pub fn is_palindrome_in_base<T: UnsignedInt>(n: T, base: u8) -> bool {
+    let digits = Digits::new(n, base);
+    digits.clone().eq(digits.rev())
+}
+
+pub fn is_palindrome<D: DecimalDigits>(n: &D) -> bool {
+    n.decimal_digits().eq(n.decimal_digits().rev())
+}
+
+pub fn is_double_base_palindrome<T: UnsignedInt>(n: T, base_a: u8, base_b: u8) -> bool {
+    is_palindrome_in_base(n, base_a) && is_palindrome_in_base(n, base_b)
+}
+
+fn power<T: UnsignedInt>(base: u8, exponent: u32) -> Option<T> {
+    (0..exponent).try_fold(T::ONE, |acc, _| acc.checked_mul(T::from_u8(base)))
+}
+
+// A palindrome of `len` digits is fixed by its first ceil(len / 2) digits, so the generator
+// walks those halves in order and mirrors each one. Longer palindromes are always larger, so
+// going length by length keeps the whole sequence sorted.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Palindromes<T: UnsignedInt> {
+    base: u8,
+    len: u32,
+    half: T,
+    ascending: bool,
+    done: bool,
+}
+
+impl<T: UnsignedInt> Palindromes<T> {
+    pub fn ascending(base: u8) -> Self {
+        assert!(base >= 2, "Base must be at least 2");
+        Palindromes {
+            base,
+            len: 1,
+            half: T::ZERO,
+            ascending: true,
+            done: false,
+        }
+    }
+
+    // Every palindrome with at most `max_digits` digits, largest first. At the full width of
+    // T the walk starts from the leading half of T::MAX, so at most one mirrored value is too
+    // big to skip.
+    pub fn descending(max_digits: u32, base: u8) -> Self {
+        assert!(base >= 2, "Base must be at least 2");
+
+        let widest = Digits::new(T::MAX, base).len() as u32;
+        let len = max_digits.min(widest);
+        let half = if len == widest {
+            T::MAX / power(base, widest - Self::half_len(len)).expect("Fits below T::MAX")
+        } else {
+            Self::largest_half(len, base)
+        };
+
+        Palindromes {
+            base,
+            len,
+            half,
+            ascending: false,
+            done: len == 0,
+        }
+    }
+
+    fn half_len(len: u32) -> u32 {
+        len.div_ceil(2)
+    }
+
+    fn smallest_half(len: u32, base: u8) -> T {
+        match len {
+            1 => T::ZERO,
+            _ => power(base, Self::half_len(len) - 1).expect("Palindrome half overflowed"),
+        }
+    }
+
+    fn largest_half(len: u32, base: u8) -> T {
+        power::<T>(base, Self::half_len(len)).expect("Palindrome half overflowed") - T::ONE
+    }
+
+    fn mirror(&self) -> Option<T> {
+        let half = Digits::new(self.half, self.base);
+        let skip_middle = (self.len % 2) as usize;
+        from_digits_in_base(half.clone().rev().chain(half.skip(skip_middle)), self.base)
+    }
+
+    fn advance(&mut self) {
+        if self.ascending {
+            if self.half == Self::largest_half(self.len, self.base) {
+                self.len += 1;
+                self.half = Self::smallest_half(self.len, self.base);
+            } else {
+                self.half += T::ONE;
+            }
+        } else if self.half == Self::smallest_half(self.len, self.base) {
+            self.len -= 1;
+            self.done = self.len == 0;
+            if !self.done {
+                self.half = Self::largest_half(self.len, self.base);
+            }
+        } else {
+            self.half -= T::ONE;
+        }
+    }
+}
+
+impl<T: UnsignedInt> Iterator for Palindromes<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            let palindrome = self.mirror();
+            match palindrome {
+                // Ascending, the first palindrome too big for T means all the rest are too.
+                None if self.ascending => self.done = true,
+                None => self.advance(),
+                Some(_) => {
+                    self.advance();
+                    return palindrome;
+                }
+            }
+        }
+        None
+    }
+}
+
+// Walks palindromes down from the largest 2n-digit one. For each, only factors a between
+// sqrt(p) and the largest n-digit number need checking, and that window is tiny near the top,
+// which is where the answer is.
+pub fn largest_palindrome_product(digits: u32) -> Option<(u64, u64, u64)> {
+    assert!(
+        (1..=9).contains(&digits),
+        "Products of two numbers with more than 9 digits do not fit in a u64"
+    );
+
+    let largest = 10u64.pow(digits) - 1;
+    let smallest = 10u64.pow(digits - 1);
+
+    Palindromes::<u64>::descending(2 * digits, 10)
+        .filter(|&p| p <= largest * largest)
+        .take_while(|&p| p >= smallest * smallest)
+        .find_map(|p| {
+            let low = p.isqrt().max(p.div_ceil(largest));
+            (low..=largest)
+                .rev()
+                .find(|&a| p.is_multiple_of(a) && p / a >= smallest && p / a <= a)
+                .map(|a| (p, p / a, a))
+        })
+}
+
+pub fn reverse_and_add(n: &BigUint) -> BigUint {
+    n + &n.reverse_digits()
+}
+
+// The number of reverse-and-add steps until a palindrome appears, or None if there is none
+// within `max_iterations`. At least one step is always taken, as in problem 55.
+pub fn lychrel_iterations(n: u64, max_iterations: u32) -> Option<u32> {
+    let mut current = BigUint::from(n);
+    (1..=max_iterations).find(|_| {
+        current = reverse_and_add(&current);
+        is_palindrome(&current)
+    })
+}
+
+pub fn is_lychrel(n: u64, max_iterations: u32) -> bool {
+    lychrel_iterations(n, max_iterations).is_none()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_test_palindromes() {
+        assert!(is_palindrome(&9009u32));
+        assert!(is_palindrome(&0u8));
+        assert!(!is_palindrome(&10u64));
+        assert!(is_palindrome(&BigUint::from(12_345_678_987_654_321u64)));
+        assert!(is_palindrome_in_base(0b1001001001u32, 2));
+        assert!(!is_palindrome_in_base(0b110u32, 2));
+    }
+
+    #[test]
+    fn ascending_matches_brute_force() {
+        let expected = (0..100_000u32)
+            .filter(|&n| is_palindrome(&n))
+            .collect::<Vec<u32>>();
+        let generated = Palindromes::<u32>::ascending(10)
+            .take_while(|&p| p < 100_000)
+            .collect::<Vec<u32>>();
+
+        assert_eq!(generated, expected);
+    }
+
+    #[test]
+    fn descending_matches_brute_force_in_other_bases() {
+        [2u8, 3, 7, 16].iter().for_each(|&base| {
+            let mut expected = (0..4096u32)
+                .filter(|&n| is_palindrome_in_base(n, base))
+                .collect::<Vec<u32>>();
+            expected.reverse();
+
+            let max_digits = Digits::new(4095u32, base).len() as u32;
+            let generated = Palindromes::<u32>::descending(max_digits, base)
+                .filter(|&p| p < 4096)
+                .collect::<Vec<u32>>();
+
+            assert_eq!(generated, expected, "base {}", base);
+        });
+    }
+
+    #[test]
+    fn ascending_stops_at_overflow() {
+        let all = Palindromes::<u8>::ascending(10).collect::<Vec<u8>>();
+        assert_eq!(all.len(), 10 + 9 + 16);
+        assert_eq!(all.last(), Some(&252));
+    }
+
+    #[test]
+    fn descending_skips_palindromes_too_big_for_the_type() {
+        let mut top = Palindromes::<u8>::descending(5, 10);
+        assert_eq!(top.next(), Some(252));
+        assert_eq!(top.next(), Some(242));
+        assert_eq!(
+            Palindromes::<u64>::descending(20, 10).next(),
+            Some(18_446_744_066_044_764_481)
+        );
+    }
+
+    #[test]
+    fn can_find_largest_palindrome_products() {
+        assert_eq!(largest_palindrome_product(1), Some((9, 1, 9)));
+        assert_eq!(largest_palindrome_product(2), Some((9009, 91, 99)));
+        assert_eq!(largest_palindrome_product(3), Some((906_609, 913, 993)));
+        assert_eq!(largest_palindrome_product(4).map(|t| t.0), Some(99_000_099));
+        assert_eq!(
+            largest_palindrome_product(7).map(|t| t.0),
+            Some(99_956_644_665_999)
+        );
+        assert_eq!(
+            largest_palindrome_product(8).map(|t| t.0),
+            Some(9_999_000_000_009_999)
+        );
+    }
+
+    #[test]
+    fn can_sum_double_base_palindromes_for_problem_36() {
+        let sum = Palindromes::<u32>::ascending(10)
+            .take_while(|&p| p < 1_000_000)
+            .filter(|&p| is_palindrome_in_base(p, 2))
+            .sum::<u32>();
+
+        assert_eq!(sum, 872_187);
+        assert!(is_double_base_palindrome(585u32, 10, 2));
+    }
+
+    #[test]
+    fn can_count_lychrel_numbers_for_problem_55() {
+        assert_eq!(lychrel_iterations(47, 50), Some(1));
+        assert_eq!(lychrel_iterations(349, 50), Some(3));
+        assert!(is_lychrel(196, 50));
+        assert!(is_lychrel(4994, 50));
+        assert!(!is_lychrel(10_677, 53));
+
+        assert_eq!((1..10_000).filter(|&n| is_lychrel(n, 50)).count(), 249);
+    }
+}
@@ -0,0 +1,230 @@
+use crate::mod_int::Modulus;
+
+const SMALL_PRIMES: [u64; 15] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47];
+
+// Checking these bases is enough for every n < 3.3 * 10^24, so all of u64.
+const MILLER_RABIN_BASES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+// No small deterministic base set is known for all of u128; past 3.3 * 10^24 the
+// extra bases make `is_prime_u128` a (very strong) probable-prime test.
+const MILLER_RABIN_BASES_U128: [u128; 20] = [
+    2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71,
+];
+
+pub fn mul_mod(a: u64, b: u64, m: u64) -> u64 {
+    ((a as u128 * b as u128) % m as u128) as u64
+}
+
+pub fn pow_mod(base: u64, exponent: u64, m: u64) -> u64 {
+    let modulus = Modulus::new(m);
+    modulus.from_residue(modulus.pow(modulus.to_residue(base), exponent))
+}
+
+pub fn is_prime(n: u64) -> bool {
+    if n < 2 {
+        return false;
+    }
+
+    for &p in SMALL_PRIMES.iter() {
+        if n == p {
+            return true;
+        } else if n.is_multiple_of(p) {
+            return false;
+        }
+    }
+
+    if n < 53 * 53 {
+        return true;
+    }
+
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+
+    let modulus = Modulus::new(n);
+    MILLER_RABIN_BASES
+        .iter()
+        .all(|&a| is_strong_probable_prime(&modulus, d, s, a))
+}
+
+pub fn mul_mod_u128(a: u128, b: u128, m: u128) -> u128 {
+    let (mut a, mut b) = (a % m, b % m);
+    if let Some(product) = a.checked_mul(b) {
+        return product % m;
+    }
+
+    let mut result = 0;
+    while b > 0 {
+        if b & 1 == 1 {
+            result = add_mod_u128(result, a, m);
+        }
+        a = add_mod_u128(a, a, m);
+        b >>= 1;
+    }
+
+    result
+}
+
+fn add_mod_u128(a: u128, b: u128, m: u128) -> u128 {
+    if a >= m - b {
+        a - (m - b)
+    } else {
+        a + b
+    }
+}
+
+pub fn pow_mod_u128(base: u128, exponent: u128, m: u128) -> u128 {
+    if m == 1 {
+        return 0;
+    }
+
+    let mut result = 1;
+    let mut base = base % m;
+    let mut exponent = exponent;
+
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = mul_mod_u128(result, base, m);
+        }
+        base = mul_mod_u128(base, base, m);
+        exponent >>= 1;
+    }
+
+    result
+}
+
+pub fn is_prime_u128(n: u128) -> bool {
+    if let Ok(small) = u64::try_from(n) {
+        return is_prime(small);
+    }
+
+    if SMALL_PRIMES.iter().any(|&p| n.is_multiple_of(p as u128)) {
+        return false;
+    }
+
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+
+    MILLER_RABIN_BASES_U128.iter().all(|&a| {
+        let mut x = pow_mod_u128(a, d, n);
+        if x == 1 || x == n - 1 {
+            return true;
+        }
+
+        (1..s).any(|_| {
+            x = mul_mod_u128(x, x, n);
+            x == n - 1
+        })
+    })
+}
+
+// Works on Montgomery residues throughout; 1 and n - 1 are compared in that form too.
+fn is_strong_probable_prime(modulus: &Modulus, d: u64, s: u32, a: u64) -> bool {
+    let one = modulus.to_residue(1);
+    let minus_one = modulus.to_residue(modulus.modulus() - 1);
+
+    let mut x = modulus.pow(modulus.to_residue(a), d);
+    if x == one || x == minus_one {
+        return true;
+    }
+
+    for _ in 1..s {
+        x = modulus.mul(x, x);
+        if x == minus_one {
+            return true;
+        }
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::segmented_sieve::SegmentedSieve;
+
+    #[test]
+    fn can_multiply_without_overflow() {
+        assert_eq!(mul_mod(u64::MAX - 1, u64::MAX - 1, u64::MAX), 1);
+        assert_eq!(
+            mul_mod(123_456_789, 987_654_321, 1_000_000_007),
+            259_106_859
+        );
+    }
+
+    #[test]
+    fn can_raise_to_a_power_mod_m() {
+        assert_eq!(pow_mod(2, 10, 1000), 24);
+        assert_eq!(pow_mod(3, 0, 7), 1);
+        assert_eq!(pow_mod(3, 5, 1), 0);
+        assert_eq!(pow_mod(2, 1_000_000_006, 1_000_000_007), 1);
+    }
+
+    #[test]
+    fn zero_and_one_are_not_prime() {
+        assert!(!is_prime(0));
+        assert!(!is_prime(1));
+    }
+
+    #[test]
+    fn matches_sieve_below_100_000() {
+        let primes = SegmentedSieve::new(100_000).primes();
+        let from_test = (0..100_000)
+            .filter(|&n| is_prime(n))
+            .map(|n| n as usize)
+            .collect::<Vec<usize>>();
+
+        assert_eq!(from_test, primes);
+    }
+
+    #[test]
+    fn can_test_large_primes() {
+        assert!(is_prime(1_000_000_007));
+        assert!(is_prime(6857));
+        assert!(is_prime(18_446_744_073_709_551_557)); // largest u64 prime
+        assert!(is_prime(4_294_967_291));
+    }
+
+    #[test]
+    fn can_test_large_composites() {
+        assert!(!is_prime(600_851_475_143));
+        assert!(!is_prime(u64::MAX));
+        assert!(!is_prime(4_294_967_291 * 4_294_967_279));
+    }
+
+    #[test]
+    fn rejects_strong_pseudoprimes() {
+        // strong pseudoprimes to several of the smallest bases
+        assert!(!is_prime(3_215_031_751));
+        assert!(!is_prime(3_825_123_056_546_413_051));
+        assert!(!is_prime(2_152_302_898_747));
+        assert!(!is_prime(3_474_749_660_383));
+        assert!(!is_prime(561));
+    }
+
+    #[test]
+    fn can_multiply_u128_without_overflow() {
+        let m = u128::MAX - 158; // largest u128 prime
+        assert_eq!(mul_mod_u128(m - 1, m - 1, m), 1);
+        assert_eq!(mul_mod_u128(1 << 100, 1 << 100, 1_000_000_007), {
+            let x = pow_mod(2, 100, 1_000_000_007) as u128;
+            x * x % 1_000_000_007
+        });
+    }
+
+    #[test]
+    fn can_test_u128_primality() {
+        assert!(is_prime_u128(2));
+        assert!(is_prime_u128(18_446_744_073_709_551_629)); // smallest prime above 2^64
+        assert!(is_prime_u128(u128::MAX - 158));
+        assert!(is_prime_u128((1 << 127) - 1));
+        assert!(!is_prime_u128(u128::MAX));
+        assert!(!is_prime_u128(
+            18_446_744_073_709_551_557 * 18_446_744_073_709_551_533
+        ));
+    }
+
+    #[test]
+    fn u128_primality_agrees_with_u64_primality() {
+        (0..5000u64).for_each(|n| assert_eq!(is_prime(n), is_prime_u128(n as u128)));
+    }
+}
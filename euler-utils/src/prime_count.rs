@@ -0,0 +1,224 @@
+use crate::segmented_sieve::SegmentedSieve;
+use std::ops::{Mul, Sub};
+
+const FIRST_PRIMES: [u64; 5] = [2, 3, 5, 7, 11];
+
+// Below this many primes it is cheaper to sieve from zero than to count with `prime_pi`.
+const NTH_PRIME_COUNTING_THRESHOLD: u64 = 1 << 16;
+
+// Every distinct value of x / k, largest first, along with a way to find each one's
+// position without a hash map.
+struct QuotientTable {
+    x: u64,
+    x_over_sqrt: u64,
+    values: Vec<u64>,
+}
+
+impl QuotientTable {
+    fn new(x: u64) -> Self {
+        let r = x.isqrt();
+        let mut values = (1..=r).map(|k| x / k).collect::<Vec<u64>>();
+        values.extend((1..x / r).rev());
+
+        QuotientTable {
+            x,
+            x_over_sqrt: x / r,
+            values,
+        }
+    }
+
+    fn index(&self, v: u64) -> usize {
+        if v >= self.x_over_sqrt {
+            (self.x / v - 1) as usize
+        } else {
+            self.values.len() - v as usize
+        }
+    }
+}
+
+// Lucy_Hedgehog's dynamic program: after processing every prime p <= sqrt(x), `table[v]`
+// holds the sum of f(q) over primes q <= v for every v of the form x / k.
+fn lucy_hedgehog<T, I, W>(x: u64, initial: I, weight: W) -> T
+where
+    T: Copy + PartialOrd + Sub<Output = T> + Mul<Output = T>,
+    I: Fn(u64) -> T,
+    W: Fn(u64) -> T,
+{
+    let quotients = QuotientTable::new(x);
+    let mut table = quotients
+        .values
+        .iter()
+        .map(|&v| initial(v))
+        .collect::<Vec<T>>();
+
+    for p in 2..=x.isqrt() {
+        let below_p = table[quotients.index(p - 1)];
+        if table[quotients.index(p)] == below_p {
+            continue;
+        }
+
+        let p_squared = p * p;
+        let w = weight(p);
+        for i in 0..quotients.values.len() {
+            let v = quotients.values[i];
+            if v < p_squared {
+                break;
+            }
+            let removed = table[quotients.index(v / p)] - below_p;
+            table[i] = table[i] - w * removed;
+        }
+    }
+
+    table[0]
+}
+
+pub fn prime_pi(x: u64) -> u64 {
+    if x < 2 {
+        return 0;
+    }
+
+    lucy_hedgehog(x, |v| v - 1, |_| 1)
+}
+
+pub fn prime_sum(x: u64) -> u128 {
+    if x < 2 {
+        return 0;
+    }
+
+    lucy_hedgehog(
+        x,
+        |v| {
+            let v = v as u128;
+            v * (v + 1) / 2 - 1
+        },
+        |p| p as u128,
+    )
+}
+
+// Rosser's theorem: p_n < n (ln n + ln ln n) for n >= 6, and Dusart (1999):
+// p_n <= n (ln n + ln ln n - 1 + (ln ln n - 2) / ln n) for n >= 688383.
+fn nth_prime_upper_bound(n: u64) -> u64 {
+    let large = n >= 688_383;
+    let n = n as f64;
+    let (ln, ln_ln) = (n.ln(), n.ln().ln());
+    let bound = if large {
+        n * (ln + ln_ln - 1.0 + (ln_ln - 2.0) / ln)
+    } else {
+        n * (ln + ln_ln)
+    };
+    bound.ceil() as u64 + 1
+}
+
+// Dusart (1999): p_n > n (ln n + ln ln n - 1) for n >= 2.
+fn nth_prime_lower_bound(n: u64) -> u64 {
+    let n = n as f64;
+    (n * (n.ln() + n.ln().ln() - 1.0)).floor() as u64 - 1
+}
+
+pub fn nth_prime(n: u64) -> Option<u64> {
+    if n == 0 {
+        return None;
+    } else if n <= FIRST_PRIMES.len() as u64 {
+        return Some(FIRST_PRIMES[n as usize - 1]);
+    }
+
+    // From n = 688383 on the two Dusart bounds leave a window about n (ln ln n - 2) / ln n
+    // wide, under 5% of n at n = 10^7. Below that Rosser's bound makes it about n wide.
+    let upper = nth_prime_upper_bound(n);
+    let (lower, primes_below_lower) = if n < NTH_PRIME_COUNTING_THRESHOLD {
+        (0, 0)
+    } else {
+        let lower = nth_prime_lower_bound(n);
+        (lower, prime_pi(lower - 1))
+    };
+
+    SegmentedSieve::range(lower as usize, upper as usize + 1)
+        .iter()
+        .nth((n - primes_below_lower - 1) as usize)
+        .map(|p| p as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::segmented_sieve::SegmentedSieve;
+
+    #[test]
+    fn small_values_of_prime_pi() {
+        let expected = [0, 0, 1, 2, 2, 3, 3, 4, 4, 4, 4, 5];
+        (0..expected.len()).for_each(|x| assert_eq!(prime_pi(x as u64), expected[x], "x = {}", x));
+    }
+
+    #[test]
+    fn prime_pi_matches_sieve_count() {
+        (0..2000).for_each(|x| {
+            let expected = SegmentedSieve::new(x + 1).count() as u64;
+            assert_eq!(prime_pi(x as u64), expected, "x = {}", x);
+        });
+    }
+
+    #[test]
+    fn prime_sum_matches_sieve_sum() {
+        (0..2000).for_each(|x| {
+            let expected = SegmentedSieve::new(x + 1)
+                .iter()
+                .map(|p| p as u128)
+                .sum::<u128>();
+            assert_eq!(prime_sum(x as u64), expected, "x = {}", x);
+        });
+    }
+
+    #[test]
+    fn can_count_primes_up_to_powers_of_ten() {
+        assert_eq!(prime_pi(1_000_000), 78_498);
+        assert_eq!(prime_pi(10_000_000), 664_579);
+        assert_eq!(prime_pi(1_000_000_000), 50_847_534);
+    }
+
+    #[test]
+    fn can_sum_primes_below_two_million() {
+        assert_eq!(prime_sum(1_999_999), 142_913_828_922);
+    }
+
+    #[test]
+    fn there_is_no_0th_prime() {
+        assert_eq!(nth_prime(0), None);
+    }
+
+    #[test]
+    fn nth_prime_matches_sieve() {
+        let primes = SegmentedSieve::new(20_000).primes();
+        primes.iter().enumerate().for_each(|(i, &p)| {
+            assert_eq!(nth_prime(i as u64 + 1), Some(p as u64), "n = {}", i + 1);
+        });
+    }
+
+    #[test]
+    fn can_get_10001st_prime() {
+        assert_eq!(nth_prime(6), Some(13));
+        assert_eq!(nth_prime(10_001), Some(104_743));
+    }
+
+    #[test]
+    fn can_get_large_nth_primes() {
+        assert_eq!(nth_prime(65_536), Some(821_641));
+        assert_eq!(nth_prime(1_000_000), Some(15_485_863));
+        assert_eq!(nth_prime(10_000_000), Some(179_424_673));
+    }
+
+    #[test]
+    fn bounds_bracket_the_nth_prime() {
+        [6, 100, 10_001, 688_382, 688_383, 1_000_000, 10_000_000]
+            .iter()
+            .for_each(|&n| {
+                let p = nth_prime(n).unwrap();
+                assert!(nth_prime_lower_bound(n) < p);
+                assert!(p < nth_prime_upper_bound(n));
+            });
+    }
+
+    #[test]
+    fn can_sum_primes_up_to_large_bounds() {
+        assert_eq!(prime_sum(1_000_000_000), 24_739_512_092_254_535);
+    }
+}
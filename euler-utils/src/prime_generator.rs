@@ -1,5 +1,6 @@
-use rayon::prelude::*;
-use std::sync::Mutex;
+use crate::primality;
+use crate::prime_count;
+use crate::segmented_sieve::SegmentedSieve;
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct PrimeGenerator {
@@ -19,8 +20,17 @@ impl PrimeGenerator {
         self.parallel_primes_up_to(n)
     }
 
+    pub fn get_primes_below_n_with_threads(&mut self, n: usize, threads: usize) -> Vec<usize> {
+        let primes = SegmentedSieve::new(n).par_primes_with_threads(threads);
+        self.add_primes_above_current_max(&primes);
+        primes
+    }
+
     pub fn sum_primes_below_n(&mut self, n: usize) -> usize {
-        self.get_primes_below_n(n).iter().filter(|&p| p < &n).sum()
+        match n {
+            0 => 0,
+            _ => prime_count::prime_sum(n as u64 - 1) as usize,
+        }
     }
 
     pub fn get_next_prime(&mut self) -> usize {
@@ -51,18 +61,11 @@ impl PrimeGenerator {
     }
 
     pub fn is_prime(&self, candidate_prime: usize) -> bool {
-        self.primes.contains(&candidate_prime)
-            || self.primes.iter().all(|prime| candidate_prime % prime != 0)
+        primality::is_prime(candidate_prime as u64)
     }
 
     pub fn nth_prime(&mut self, n: usize) -> Option<usize> {
-        if n == 0 {
-            None
-        } else if n == 1 {
-            return Some(2);
-        } else {
-            return self.nth((n - 2).try_into().unwrap_or(2));
-        }
+        prime_count::nth_prime(n as u64).map(|p| p as usize)
     }
 
     pub fn is_prime_with_small_primes(candidate_prime: usize, small_primes: &[usize]) -> bool {
@@ -71,7 +74,7 @@ impl PrimeGenerator {
             .all(|&prime| candidate_prime % prime != 0)
     }
 
-    fn get_primes_below_n_with_sieve(limit: usize) -> Vec<usize> {
+    pub(crate) fn get_primes_below_n_with_sieve(limit: usize) -> Vec<usize> {
         let mut sieve = vec![true; (limit + 1) as usize];
         sieve[0] = false;
         sieve[1] = false;
@@ -92,57 +95,30 @@ impl PrimeGenerator {
     }
 
     fn get_primes_below_n_in_series(&mut self, n: usize) -> Vec<usize> {
-        Self::get_primes_below_n_with_sieve(n)
+        let primes = SegmentedSieve::new(n).primes();
+        self.add_primes_above_current_max(&primes);
+        primes
+    }
+
+    fn add_primes_above_current_max(&mut self, primes: &[usize]) {
+        let current_max = self.current_max().unwrap_or(0);
+        primes
+            .iter()
+            .filter(|&&p| p > current_max)
+            .for_each(|&p| self.add_prime(p));
     }
 
     fn parallel_primes_up_to(&mut self, n: usize) -> Vec<usize> {
-        let parallel_processing_threshold = 50;
+        let sieve = SegmentedSieve::new(n);
 
-        if n <= 2 {
-            return vec![];
-        } else if n < parallel_processing_threshold {
+        if sieve.segment_count() < 2 {
             return self.get_primes_below_n_in_series(n);
         }
 
-        let small_primes = Self::generate_small_primes(n);
-        let primes_in_parallel = Self::mark_larger_primes_parallel(n, small_primes.as_slice());
-
-        let primes: Vec<usize> = primes_in_parallel
-            .iter()
-            .filter(|k| k < &&n)
-            .copied()
-            .collect::<Vec<usize>>();
-
-        primes.iter().for_each(|&p| self.add_prime(p));
-
+        let primes = sieve.par_primes();
+        self.add_primes_above_current_max(&primes);
         primes
     }
-
-    fn get_small_prime_limit(n: usize) -> usize {
-        (n as f64).sqrt().ceil() as usize
-    }
-
-    fn generate_small_primes(n: usize) -> Vec<usize> {
-        let limit = Self::get_small_prime_limit(n);
-        Self::get_primes_below_n_with_sieve(limit)
-    }
-
-    fn mark_larger_primes_parallel(n: usize, small_primes: &[usize]) -> Vec<usize> {
-        let primes_in_parallel = Mutex::new(small_primes.to_vec());
-        let small_prime_limit = Self::get_small_prime_limit(n);
-
-        (small_prime_limit + 1..n)
-            .into_par_iter()
-            .filter(|&candidate| Self::is_prime_with_small_primes(candidate, small_primes))
-            .for_each(|candidate| {
-                let mut primes = primes_in_parallel.lock().unwrap();
-                primes.push(candidate);
-            });
-
-        let mut result = primes_in_parallel.into_inner().unwrap();
-        result.sort_unstable();
-        result
-    }
 }
 
 impl Default for PrimeGenerator {
@@ -231,6 +207,20 @@ mod tests {
         });
     }
 
+    #[test]
+    fn nth_prime_does_not_depend_on_generator_state() {
+        let mut p = PrimeGenerator::new();
+        p.get_primes_below_n(1000);
+
+        assert_eq!(p.nth_prime(1), Some(2));
+        assert_eq!(p.nth_prime(2), Some(3));
+        assert_eq!(p.nth_prime(6), Some(13));
+        assert_eq!(p.nth_prime(10_001), Some(104_743));
+
+        p.get_next_prime();
+        assert_eq!(p.nth_prime(6), Some(13));
+    }
+
     #[test]
     #[should_panic]
     fn test_next_none_arm() {
@@ -262,6 +252,21 @@ mod tests {
         test_series_equivalent_to_parallel_for_n(1000);
     }
 
+    #[test]
+    fn getting_primes_in_series_is_equivalent_to_in_parallel_for_several_segments() {
+        test_series_equivalent_to_parallel_for_n(3_000_000);
+    }
+
+    #[test]
+    fn can_get_primes_below_n_with_a_fixed_number_of_threads() {
+        let mut p = PrimeGenerator::new();
+        let expected = primes_below_1000();
+
+        assert_eq!(p.get_primes_below_n_with_threads(1000, 1), expected);
+        assert_eq!(p.get_primes_below_n_with_threads(1000, 3), expected);
+        assert_eq!(p.primes, expected);
+    }
+
     #[test]
     fn test_sum_primes_lt_10() {
         let mut p = PrimeGenerator::new();
@@ -289,6 +294,14 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn test_sum_primes_below_two_million() {
+        let mut p = PrimeGenerator::new();
+
+        assert_eq!(p.sum_primes_below_n(2_000_000), 142_913_828_922);
+        assert_eq!(p.sum_primes_below_n(0), 0);
+    }
+
     #[test]
     fn test_sieve_up_to_10() {
         let primes = PrimeGenerator::get_primes_below_n_with_sieve(10);
@@ -398,6 +411,25 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn get_primes_below_n_does_not_cache_duplicates() {
+        let mut p = PrimeGenerator::new();
+        p.get_primes_below_n(30);
+        p.get_primes_below_n(20);
+
+        assert_eq!(p.primes, vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+        assert_eq!(p.get_next_prime(), 31);
+    }
+
+    #[test]
+    fn test_get_primes_below_ten_million() {
+        let mut p = PrimeGenerator::new();
+        let primes = p.get_primes_below_n(10_000_000);
+
+        assert_eq!(primes.len(), 664_579);
+        assert_eq!(primes.last(), Some(&9_999_991));
+    }
+
     #[test]
     fn test_is_prime() {
         let mut p = PrimeGenerator::new();
@@ -405,4 +437,13 @@ mod tests {
 
         assert!(p.is_prime(3));
     }
+
+    #[test]
+    fn is_prime_does_not_depend_on_cached_primes() {
+        let p = PrimeGenerator::new();
+
+        assert!(p.is_prime(1_000_000_007));
+        assert!(!p.is_prime(600_851_475_143));
+        assert!(!p.is_prime(9));
+    }
 }
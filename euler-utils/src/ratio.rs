@@ -0,0 +1,406 @@
+use crate::integer::{Semiring, SignedInt};
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+// Always stored in lowest terms with a positive denominator, so equal values have equal
+// representations and the derived equality and hash are numeric.
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct Ratio<T: SignedInt> {
+    numer: T,
+    denom: T,
+}
+
+impl<T: SignedInt> Ratio<T> {
+    pub fn new(numer: T, denom: T) -> Self {
+        assert!(!denom.is_zero(), "Ratio has a zero denominator");
+
+        let g = numer.gcd(&denom);
+        let (numer, denom) = (numer / g.clone(), denom / g);
+        if denom.is_negative() {
+            Ratio {
+                numer: -numer,
+                denom: -denom,
+            }
+        } else {
+            Ratio { numer, denom }
+        }
+    }
+
+    pub fn from_integer(n: T) -> Self {
+        Ratio {
+            numer: n,
+            denom: T::one(),
+        }
+    }
+
+    pub fn zero() -> Self {
+        Ratio::from_integer(T::zero())
+    }
+
+    pub fn one() -> Self {
+        Ratio::from_integer(T::one())
+    }
+
+    pub fn numer(&self) -> &T {
+        &self.numer
+    }
+
+    pub fn denom(&self) -> &T {
+        &self.denom
+    }
+
+    pub fn into_parts(self) -> (T, T) {
+        (self.numer, self.denom)
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.denom == T::one()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.numer.is_zero()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.numer.is_negative()
+    }
+
+    pub fn abs(&self) -> Self {
+        Ratio {
+            numer: self.numer.abs(),
+            denom: self.denom.clone(),
+        }
+    }
+
+    pub fn recip(&self) -> Self {
+        Ratio::new(self.denom.clone(), self.numer.clone())
+    }
+
+    pub fn floor(&self) -> T {
+        self.numer.div_floor(&self.denom)
+    }
+
+    // gcd(a, b) = 1 gives gcd(a^e, b^e) = 1, so the parts are powered separately and the
+    // result needs no reducing.
+    pub fn pow(&self, exponent: i32) -> Self {
+        let base = if exponent < 0 {
+            self.recip()
+        } else {
+            self.clone()
+        };
+        Ratio {
+            numer: pow_by_squaring(base.numer, exponent.unsigned_abs()),
+            denom: pow_by_squaring(base.denom, exponent.unsigned_abs()),
+        }
+    }
+
+    // [a0; a1, a2, ..., an] = a0 + 1 / (a1 + 1 / (a2 + ... + 1 / an)), folded from the back.
+    pub fn from_continued_fraction(terms: &[T]) -> Self {
+        let (last, rest) = terms
+            .split_last()
+            .expect("A continued fraction needs at least one term");
+
+        rest.iter()
+            .rev()
+            .fold(Ratio::from_integer(last.clone()), |x, a| {
+                Ratio::from_integer(a.clone()) + x.recip()
+            })
+    }
+
+    // Euclid's algorithm on numerator and denominator, with floor division so negative values
+    // get a negative first term and positive terms after it.
+    pub fn to_continued_fraction(&self) -> Vec<T> {
+        let mut terms = Vec::new();
+        let (mut numer, mut denom) = (self.numer.clone(), self.denom.clone());
+
+        while !denom.is_zero() {
+            let a = numer.div_floor(&denom);
+            let remainder = numer - a.clone() * denom.clone();
+            terms.push(a);
+            numer = denom;
+            denom = remainder;
+        }
+
+        terms
+    }
+}
+
+// h_n / k_n with h_n = a_n h_(n-1) + h_(n-2) and k_n = a_n k_(n-1) + k_(n-2), which is cheaper
+// than refolding the continued fraction for every prefix.
+pub fn convergents<T: SignedInt>(terms: &[T]) -> Vec<Ratio<T>> {
+    let (mut h, mut h_previous) = (T::one(), T::zero());
+    let (mut k, mut k_previous) = (T::zero(), T::one());
+
+    terms
+        .iter()
+        .map(|a| {
+            let h_next = a.clone() * h.clone() + h_previous.clone();
+            let k_next = a.clone() * k.clone() + k_previous.clone();
+            h_previous = std::mem::replace(&mut h, h_next.clone());
+            k_previous = std::mem::replace(&mut k, k_next.clone());
+            Ratio::new(h_next, k_next)
+        })
+        .collect()
+}
+
+fn pow_by_squaring<T: SignedInt>(base: T, exponent: u32) -> T {
+    let mut result = T::one();
+    let mut base = base;
+    let mut exponent = exponent;
+
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = result * base.clone();
+        }
+        exponent >>= 1;
+        if exponent > 0 {
+            base = base.clone() * base;
+        }
+    }
+
+    result
+}
+
+impl<T: SignedInt> From<T> for Ratio<T> {
+    fn from(n: T) -> Self {
+        Ratio::from_integer(n)
+    }
+}
+
+impl<T: SignedInt> Semiring for Ratio<T> {
+    fn zero_like(&self) -> Self {
+        Ratio::zero()
+    }
+
+    fn one_like(&self) -> Self {
+        Ratio::one()
+    }
+}
+
+impl<T: SignedInt> fmt::Display for Ratio<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.numer)
+        } else {
+            write!(f, "{}/{}", self.numer, self.denom)
+        }
+    }
+}
+
+impl<T: SignedInt> Ord for Ratio<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.numer.clone() * other.denom.clone()).cmp(&(other.numer.clone() * self.denom.clone()))
+    }
+}
+
+impl<T: SignedInt> PartialOrd for Ratio<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: SignedInt> Neg for Ratio<T> {
+    type Output = Ratio<T>;
+
+    fn neg(self) -> Ratio<T> {
+        Ratio {
+            numer: -self.numer,
+            denom: self.denom,
+        }
+    }
+}
+
+// Dividing out gcd(b, d) first keeps the intermediate products small, which matters when T
+// is a primitive that could overflow.
+impl<T: SignedInt> Add for Ratio<T> {
+    type Output = Ratio<T>;
+
+    fn add(self, other: Ratio<T>) -> Ratio<T> {
+        let g = self.denom.gcd(&other.denom);
+        let b = self.denom / g.clone();
+        let d = other.denom.clone() / g;
+        Ratio::new(self.numer * d + other.numer * b.clone(), b * other.denom)
+    }
+}
+
+impl<T: SignedInt> Sub for Ratio<T> {
+    type Output = Ratio<T>;
+
+    fn sub(self, other: Ratio<T>) -> Ratio<T> {
+        self + -other
+    }
+}
+
+// Cross-cancelling a/b * c/d by gcd(a, d) and gcd(c, b) leaves a product in lowest terms.
+impl<T: SignedInt> Mul for Ratio<T> {
+    type Output = Ratio<T>;
+
+    fn mul(self, other: Ratio<T>) -> Ratio<T> {
+        if self.is_zero() || other.is_zero() {
+            return Ratio::zero();
+        }
+
+        let g1 = self.numer.gcd(&other.denom);
+        let g2 = other.numer.gcd(&self.denom);
+
+        Ratio {
+            numer: (self.numer / g1.clone()) * (other.numer / g2.clone()),
+            denom: (self.denom / g2) * (other.denom / g1),
+        }
+    }
+}
+
+impl<T: SignedInt> Div for Ratio<T> {
+    type Output = Ratio<T>;
+
+    fn div(self, other: Ratio<T>) -> Ratio<T> {
+        Mul::mul(self, other.recip())
+    }
+}
+
+macro_rules! forward_ref_op {
+    ($($trait:ident, $method:ident);*) => {
+        $(
+            impl<T: SignedInt> $trait<&Ratio<T>> for &Ratio<T> {
+                type Output = Ratio<T>;
+
+                fn $method(self, other: &Ratio<T>) -> Ratio<T> {
+                    self.clone().$method(other.clone())
+                }
+            }
+        )*
+    };
+}
+
+forward_ref_op!(Add, add; Sub, sub; Mul, mul; Div, div);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::big_int::BigInt;
+
+    fn r(numer: i64, denom: i64) -> Ratio<i64> {
+        Ratio::new(numer, denom)
+    }
+
+    #[test]
+    fn normalizes_on_construction() {
+        assert_eq!(r(6, 8), r(3, 4));
+        assert_eq!(r(3, -4).into_parts(), (-3, 4));
+        assert_eq!(r(-3, -4), r(3, 4));
+        assert_eq!(r(0, -5).into_parts(), (0, 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "zero denominator")]
+    fn zero_denominator_panics() {
+        r(1, 0);
+    }
+
+    #[test]
+    fn arithmetic_stays_in_lowest_terms() {
+        assert_eq!(r(1, 6) + r(1, 3), r(1, 2));
+        assert_eq!(r(1, 6) - r(1, 3), r(-1, 6));
+        assert_eq!(r(2, 3) * r(9, 4), r(3, 2));
+        assert_eq!(r(2, 3) / r(4, 9), r(3, 2));
+        assert_eq!(r(2, 3) * r(0, 1), Ratio::zero());
+        assert_eq!((r(0, 1) * r(5, 7)).into_parts(), (0, 1));
+        assert_eq!(&r(1, 2) + &r(1, 2), Ratio::one());
+        assert_eq!(-r(1, 2), r(-1, 2));
+    }
+
+    #[test]
+    fn can_compare_ratios() {
+        let mut values = vec![r(1, 2), r(-1, 3), r(2, 3), r(0, 1), r(-1, 2)];
+        values.sort();
+        assert_eq!(values, vec![r(-1, 2), r(-1, 3), r(0, 1), r(1, 2), r(2, 3)]);
+        assert!(r(49, 98) == r(4, 8));
+    }
+
+    #[test]
+    fn can_take_floors_and_powers() {
+        assert_eq!(r(7, 2).floor(), 3);
+        assert_eq!(r(-7, 2).floor(), -4);
+        assert_eq!(r(2, 3).pow(3), r(8, 27));
+        assert_eq!(r(2, 3).pow(-2), r(9, 4));
+        assert_eq!(r(5, 1).pow(0), Ratio::one());
+        assert_eq!(r(-2, 3).pow(5), r(-32, 243));
+        assert_eq!(r(-2, 3).pow(-3), r(-27, 8));
+    }
+
+    #[test]
+    fn large_powers_stay_in_lowest_terms() {
+        let big = |n: i64| BigInt::from(n);
+        let x = Ratio::new(big(-3), big(2));
+
+        assert_eq!(
+            x.pow(1001).into_parts(),
+            (big(-3).pow(1001), big(2).pow(1001))
+        );
+        assert_eq!(
+            x.pow(-1000).into_parts(),
+            (big(2).pow(1000), big(3).pow(1000))
+        );
+        assert_eq!(
+            Ratio::new(big(6), big(4)).pow(2),
+            Ratio::new(big(9), big(4))
+        );
+    }
+
+    #[test]
+    fn can_display_ratios() {
+        assert_eq!(r(3, 4).to_string(), "3/4");
+        assert_eq!(r(-8, 4).to_string(), "-2");
+    }
+
+    #[test]
+    fn continued_fractions_round_trip() {
+        assert_eq!(
+            Ratio::from_continued_fraction(&[3i64, 7, 15, 1]),
+            r(355, 113)
+        );
+        assert_eq!(r(355, 113).to_continued_fraction(), vec![3, 7, 16]);
+        assert_eq!(r(-7, 3).to_continued_fraction(), vec![-3, 1, 2]);
+        assert_eq!(Ratio::from_continued_fraction(&[-3i64, 1, 2]), r(-7, 3));
+        assert_eq!(Ratio::from_continued_fraction(&[4i64]), r(4, 1));
+    }
+
+    #[test]
+    fn convergents_match_folding_each_prefix() {
+        let terms = [1i64, 2, 2, 2, 2, 2, 2, 2];
+        let expected = (1..=terms.len())
+            .map(|n| Ratio::from_continued_fraction(&terms[..n]))
+            .collect::<Vec<Ratio<i64>>>();
+
+        assert_eq!(convergents(&terms), expected);
+        assert_eq!(convergents(&terms)[7], r(577, 408));
+    }
+
+    #[test]
+    fn problem_33_digit_cancelling_fractions() {
+        let product = (10..100i64)
+            .flat_map(|n| (n + 1..100).map(move |d| (n, d)))
+            .filter(|&(n, d)| n % 10 == d / 10 && d % 10 != 0 && n * (d % 10) == d * (n / 10))
+            .fold(Ratio::one(), |acc, (n, d)| acc * r(n, d));
+
+        assert_eq!(product, r(1, 100));
+    }
+
+    #[test]
+    fn problem_57_square_root_convergents_with_big_ints() {
+        let terms = std::iter::once(1)
+            .chain(std::iter::repeat(2))
+            .take(1001)
+            .map(BigInt::from)
+            .collect::<Vec<BigInt>>();
+
+        let heavier = convergents(&terms)
+            .iter()
+            .skip(1)
+            .filter(|c| c.numer().to_string().len() > c.denom().to_string().len())
+            .count();
+
+        assert_eq!(heavier, 153);
+    }
+}
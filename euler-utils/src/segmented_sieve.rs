@@ -0,0 +1,403 @@
+use crate::prime_generator::PrimeGenerator;
+use rayon::prelude::*;
+use rayon::ThreadPoolBuilder;
+
+// 32 KiB of bits per segment keeps the working set inside a typical L1 cache.
+const DEFAULT_SEGMENT_BYTES: usize = 32 * 1024;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct SegmentedSieve {
+    start: usize,
+    limit: usize,
+    includes_two: bool,
+    segment_words: usize,
+    base_primes: Vec<usize>,
+}
+
+impl SegmentedSieve {
+    pub fn new(limit: usize) -> Self {
+        Self::with_segment_bytes(limit, DEFAULT_SEGMENT_BYTES)
+    }
+
+    pub fn with_segment_bytes(limit: usize, segment_bytes: usize) -> Self {
+        Self::range_with_segment_bytes(0, limit, segment_bytes)
+    }
+
+    pub fn range(start: usize, limit: usize) -> Self {
+        Self::range_with_segment_bytes(start, limit, DEFAULT_SEGMENT_BYTES)
+    }
+
+    // Segments always begin on an even number so that bit 0 of each one is odd.
+    pub fn range_with_segment_bytes(start: usize, limit: usize, segment_bytes: usize) -> Self {
+        let segment_words = (segment_bytes / 8).max(1);
+        let base_primes = if limit > 2 {
+            PrimeGenerator::get_primes_below_n_with_sieve((limit - 1).isqrt())
+                .into_iter()
+                .skip(1)
+                .collect()
+        } else {
+            vec![]
+        };
+
+        SegmentedSieve {
+            start: start & !1,
+            limit,
+            includes_two: start <= 2 && limit > 2,
+            segment_words,
+            base_primes,
+        }
+    }
+
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    pub fn segment_span(&self) -> usize {
+        self.segment_words * 64 * 2
+    }
+
+    pub fn segment_count(&self) -> usize {
+        self.limit
+            .saturating_sub(self.start)
+            .div_ceil(self.segment_span())
+    }
+
+    fn segment_bounds(&self, index: usize) -> (usize, usize) {
+        let low = self.start + index * self.segment_span();
+        let high = (low + self.segment_span()).min(self.limit);
+        (low, high)
+    }
+
+    // Bit i of a segment starting at `low` stands for the odd number low + 2i + 1.
+    fn sieve_segment_bits(&self, index: usize, bits: &mut Vec<u64>) -> (usize, usize) {
+        let (low, high) = self.segment_bounds(index);
+        let n_odds = (high - low) / 2;
+
+        bits.clear();
+        bits.resize(n_odds.div_ceil(64), u64::MAX);
+        if n_odds % 64 != 0 {
+            if let Some(last) = bits.last_mut() {
+                *last = (1 << (n_odds % 64)) - 1;
+            }
+        }
+
+        if low == 0 && n_odds > 0 {
+            bits[0] &= !1;
+        }
+
+        for &prime in self.base_primes.iter() {
+            let square = prime * prime;
+            if square >= high {
+                break;
+            }
+
+            let mut multiple = square.max(low.div_ceil(prime) * prime);
+            if multiple % 2 == 0 {
+                multiple += prime;
+            }
+
+            let mut i = (multiple - low) / 2;
+            while i < n_odds {
+                bits[i / 64] &= !(1 << (i % 64));
+                i += prime;
+            }
+        }
+
+        (low, n_odds)
+    }
+
+    pub fn sieve_segment<F: FnMut(usize)>(&self, index: usize, bits: &mut Vec<u64>, mut f: F) {
+        let (low, _) = self.sieve_segment_bits(index, bits);
+
+        if index == 0 && self.includes_two {
+            f(2);
+        }
+
+        for (w, &word) in bits.iter().enumerate() {
+            let mut word = word;
+            while word != 0 {
+                let i = w * 64 + word.trailing_zeros() as usize;
+                f(low + 2 * i + 1);
+                word &= word - 1;
+            }
+        }
+    }
+
+    pub fn count_segment(&self, index: usize, bits: &mut Vec<u64>) -> usize {
+        self.sieve_segment_bits(index, bits);
+        let evens = usize::from(index == 0 && self.includes_two);
+
+        evens
+            + bits
+                .iter()
+                .map(|word| word.count_ones() as usize)
+                .sum::<usize>()
+    }
+
+    pub fn for_each_prime<F: FnMut(usize)>(&self, mut f: F) {
+        let mut bits = Vec::with_capacity(self.segment_words);
+        (0..self.segment_count()).for_each(|index| self.sieve_segment(index, &mut bits, &mut f));
+    }
+
+    pub fn primes(&self) -> Vec<usize> {
+        let mut primes = Vec::new();
+        self.for_each_prime(|p| primes.push(p));
+        primes
+    }
+
+    pub fn count(&self) -> usize {
+        let mut bits = Vec::with_capacity(self.segment_words);
+        (0..self.segment_count())
+            .map(|index| self.count_segment(index, &mut bits))
+            .sum()
+    }
+
+    pub fn par_primes(&self) -> Vec<usize> {
+        (0..self.segment_count())
+            .into_par_iter()
+            .map_init(
+                || Vec::with_capacity(self.segment_words),
+                |bits, index| {
+                    let mut primes = Vec::new();
+                    self.sieve_segment(index, bits, |p| primes.push(p));
+                    primes
+                },
+            )
+            .collect::<Vec<Vec<usize>>>()
+            .concat()
+    }
+
+    pub fn par_count(&self) -> usize {
+        (0..self.segment_count())
+            .into_par_iter()
+            .map_init(
+                || Vec::with_capacity(self.segment_words),
+                |bits, index| self.count_segment(index, bits),
+            )
+            .sum()
+    }
+
+    pub fn par_primes_with_threads(&self, threads: usize) -> Vec<usize> {
+        Self::with_thread_pool(threads, || self.par_primes())
+    }
+
+    pub fn par_count_with_threads(&self, threads: usize) -> usize {
+        Self::with_thread_pool(threads, || self.par_count())
+    }
+
+    // A thread count of 0 lets rayon pick one, same as the global pool.
+    fn with_thread_pool<T: Send, F: FnOnce() -> T + Send>(threads: usize, f: F) -> T {
+        ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .expect("Failed to build rayon thread pool")
+            .install(f)
+    }
+
+    pub fn iter(&self) -> SegmentedPrimes<'_> {
+        SegmentedPrimes {
+            sieve: self,
+            next_segment: 0,
+            bits: Vec::with_capacity(self.segment_words),
+            segment: Vec::new(),
+            position: 0,
+        }
+    }
+}
+
+pub fn primes_below(n: usize) -> Vec<usize> {
+    SegmentedSieve::new(n).primes()
+}
+
+#[derive(Debug)]
+pub struct SegmentedPrimes<'a> {
+    sieve: &'a SegmentedSieve,
+    next_segment: usize,
+    bits: Vec<u64>,
+    segment: Vec<usize>,
+    position: usize,
+}
+
+impl Iterator for SegmentedPrimes<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.position == self.segment.len() {
+            if self.next_segment == self.sieve.segment_count() {
+                return None;
+            }
+
+            self.segment.clear();
+            self.position = 0;
+            let segment = &mut self.segment;
+            self.sieve
+                .sieve_segment(self.next_segment, &mut self.bits, |p| segment.push(p));
+            self.next_segment += 1;
+        }
+
+        self.position += 1;
+        Some(self.segment[self.position - 1])
+    }
+}
+
+impl<'a> IntoIterator for &'a SegmentedSieve {
+    type Item = usize;
+    type IntoIter = SegmentedPrimes<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn simple_primes_below(n: usize) -> Vec<usize> {
+        if n < 2 {
+            return vec![];
+        }
+        PrimeGenerator::get_primes_below_n_with_sieve(n - 1)
+    }
+
+    #[test]
+    fn no_primes_below_3_other_than_2() {
+        assert_eq!(primes_below(0), vec![]);
+        assert_eq!(primes_below(1), vec![]);
+        assert_eq!(primes_below(2), vec![]);
+        assert_eq!(primes_below(3), vec![2]);
+    }
+
+    #[test]
+    fn can_sieve_primes_below_30() {
+        assert_eq!(primes_below(30), vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+    }
+
+    #[test]
+    fn limit_is_exclusive() {
+        assert_eq!(primes_below(29), vec![2, 3, 5, 7, 11, 13, 17, 19, 23]);
+        assert_eq!(primes_below(30).len(), 10);
+    }
+
+    #[test]
+    fn matches_simple_sieve_across_many_tiny_segments() {
+        (0..600).for_each(|n| {
+            let sieve = SegmentedSieve::with_segment_bytes(n, 8);
+            assert_eq!(sieve.primes(), simple_primes_below(n), "n = {}", n);
+        });
+    }
+
+    #[test]
+    fn matches_simple_sieve_for_100_000() {
+        let sieve = SegmentedSieve::with_segment_bytes(100_000, 64);
+        assert_eq!(sieve.primes(), simple_primes_below(100_000));
+    }
+
+    #[test]
+    fn can_count_primes() {
+        assert_eq!(SegmentedSieve::new(10).count(), 4);
+        assert_eq!(SegmentedSieve::new(1_000_000).count(), 78_498);
+        assert_eq!(
+            SegmentedSieve::with_segment_bytes(1_000_000, 16).count(),
+            78_498
+        );
+    }
+
+    #[test]
+    fn iterator_matches_vector_of_primes() {
+        let sieve = SegmentedSieve::with_segment_bytes(10_000, 16);
+        let iterated = sieve.iter().collect::<Vec<usize>>();
+
+        assert_eq!(iterated, sieve.primes());
+    }
+
+    #[test]
+    fn can_take_first_primes_lazily() {
+        let sieve = SegmentedSieve::new(1_000_000_000_000);
+        let first = (&sieve).into_iter().take(5).collect::<Vec<usize>>();
+
+        assert_eq!(first, vec![2, 3, 5, 7, 11]);
+    }
+
+    #[test]
+    fn parallel_sieve_matches_serial_sieve() {
+        (0..300).for_each(|n| {
+            let sieve = SegmentedSieve::with_segment_bytes(n, 8);
+            assert_eq!(sieve.par_primes(), sieve.primes(), "n = {}", n);
+        });
+
+        let sieve = SegmentedSieve::with_segment_bytes(1_000_000, 256);
+        assert_eq!(sieve.par_primes(), sieve.primes());
+    }
+
+    #[test]
+    fn parallel_count_matches_serial_count() {
+        let sieve = SegmentedSieve::with_segment_bytes(1_000_000, 64);
+        assert_eq!(sieve.par_count(), sieve.count());
+    }
+
+    #[test]
+    fn can_choose_number_of_threads() {
+        let sieve = SegmentedSieve::with_segment_bytes(100_000, 32);
+        let serial = sieve.primes();
+
+        (1..=4).for_each(|threads| {
+            assert_eq!(sieve.par_primes_with_threads(threads), serial);
+            assert_eq!(sieve.par_count_with_threads(threads), serial.len());
+        });
+    }
+
+    #[test]
+    fn can_sieve_a_range_that_does_not_start_at_zero() {
+        assert_eq!(
+            SegmentedSieve::range(10, 30).primes(),
+            vec![11, 13, 17, 19, 23, 29]
+        );
+        assert_eq!(
+            SegmentedSieve::range(11, 29).primes(),
+            vec![11, 13, 17, 19, 23]
+        );
+        assert_eq!(SegmentedSieve::range(2, 5).primes(), vec![2, 3]);
+        assert_eq!(SegmentedSieve::range(3, 5).primes(), vec![3]);
+        assert_eq!(SegmentedSieve::range(30, 10).primes(), vec![]);
+    }
+
+    #[test]
+    fn ranges_match_simple_sieve() {
+        let all = simple_primes_below(3000);
+
+        (0..300).step_by(7).for_each(|start| {
+            (start..3000).step_by(97).for_each(|limit| {
+                let sieve = SegmentedSieve::range_with_segment_bytes(start, limit, 8);
+                let expected = all
+                    .iter()
+                    .copied()
+                    .filter(|&p| start <= p && p < limit)
+                    .collect::<Vec<usize>>();
+
+                assert_eq!(sieve.primes(), expected, "[{}, {})", start, limit);
+                assert_eq!(sieve.count(), expected.len());
+            });
+        });
+    }
+
+    #[test]
+    fn can_count_primes_in_a_high_range() {
+        let sieve = SegmentedSieve::range(1_000_000_000, 1_000_100_000);
+        assert_eq!(sieve.count(), 4_832);
+        assert_eq!(sieve.iter().next(), Some(1_000_000_007));
+    }
+
+    #[test]
+    fn segment_count_covers_the_limit() {
+        let sieve = SegmentedSieve::with_segment_bytes(1000, 8);
+
+        assert_eq!(sieve.segment_span(), 128);
+        assert_eq!(sieve.segment_count(), 8);
+        assert_eq!(sieve.limit(), 1000);
+    }
+}
@@ -1,43 +1,68 @@
+use crate::divisors::{divisor_count, sorted_divisors};
+use crate::figurate::triangular;
+
 pub fn get_first_with_n_divisors(n: u32) -> u32 {
     let i = (1..).find(|&i| count_divisors_of_number(get_triangular_number(i)) > n).unwrap();
     get_triangular_number(i)
 }
 
-pub fn get_triangular_number(n: u32) -> u32 {
-    n * (n + 1) / 2
-}
+// T(n) = n(n + 1) / 2 and gcd(n, n + 1) = 1, so d(T(n)) is d(n / 2) d(n + 1) or
+// d(n) d((n + 1) / 2), read straight from a tau table that doubles whenever it runs out.
+pub fn first_triangular_with_more_divisors_than(threshold: u64) -> (u64, u64) {
+    let (mut start, mut limit) = (1, 1024);
+    let mut table = Vec::new();
 
-pub fn count_divisors_of_number(n: u32) -> u32 {
-    let mut divisors = vec![];
-    let max_search_term = (n as f64).sqrt() as u32;
+    loop {
+        extend_tau_table(&mut table, limit + 1);
+        let tau = |k: usize| table[k] as u64;
 
-    (1..=max_search_term).filter(|i| n % i == 0).for_each(|i| {
-        divisors.push(i);
-        if i != n / i {
-            divisors.push(n / i);
+        if let Some(n) = (start..=limit).find(|&n| {
+            let divisors = if n.is_multiple_of(2) {
+                tau(n / 2) * tau(n + 1)
+            } else {
+                tau(n) * tau(n.div_ceil(2))
+            };
+            divisors > threshold
+        }) {
+            let n = n as u64;
+            return (n, triangular(n).expect("Triangular number overflowed u64"));
         }
-    });
 
-    divisors.len() as u32
+        start = limit + 1;
+        limit *= 2;
+    }
 }
 
-pub fn get_divisors_of_number(n: u32) -> Vec<u32> {
-    let mut divisors = vec![];
-    let max_search_term = (n as f64).sqrt() as u32;
+// Counts divisors for the new range [table.len(), limit] only, so doubling the table costs a
+// segment's worth of work instead of a fresh sieve over everything below it.
+fn extend_tau_table(table: &mut Vec<u32>, limit: usize) {
+    let start = table.len();
+    table.resize(limit + 1, 0);
 
-    (1..=max_search_term).filter(|i| n % i == 0).for_each(|i| {
-        divisors.push(i);
-        if i != n / i {
-            divisors.push(n / i);
-        }
+    (1..=limit).for_each(|d| {
+        let first_multiple = start.div_ceil(d).max(1) * d;
+        (first_multiple..=limit)
+            .step_by(d)
+            .for_each(|multiple| table[multiple] += 1);
     });
+}
 
-    divisors.sort();
-    divisors
+pub fn get_triangular_number(n: u32) -> u32 {
+    triangular(n).expect("Triangular number overflowed u32")
 }
 
-#[cfg(test)]
+pub fn count_divisors_of_number(n: u32) -> u32 {
+    divisor_count(n as u64) as u32
+}
 
+pub fn get_divisors_of_number(n: u32) -> Vec<u32> {
+    sorted_divisors(n as u64)
+        .iter()
+        .map(|&d| d as u32)
+        .collect::<Vec<u32>>()
+}
+
+#[cfg(test)]
 mod tests {
     use super::*;
 
@@ -48,6 +73,58 @@ mod tests {
         assert_eq!(get_triangular_number(3), 6);
         assert_eq!(get_triangular_number(4), 10);
         assert_eq!(get_triangular_number(5), 15);
+        assert_eq!(get_triangular_number(92_681), 4_294_930_221);
+    }
+
+    #[test]
+    #[should_panic(expected = "overflowed")]
+    fn triangular_number_overflow_panics() {
+        get_triangular_number(92_682);
+    }
+
+    #[test]
+    fn coprime_split_search_matches_direct_search() {
+        (0..100).for_each(|threshold| {
+            let (n, t) = first_triangular_with_more_divisors_than(threshold);
+            assert_eq!(t, get_first_with_n_divisors(threshold as u32) as u64);
+            assert_eq!(t, n * (n + 1) / 2);
+        });
+    }
+
+    #[test]
+    fn extended_tau_table_matches_a_fresh_one() {
+        let mut extended = Vec::new();
+        [10, 11, 37, 1024, 2049].iter().for_each(|&limit| {
+            extend_tau_table(&mut extended, limit);
+            let mut fresh = Vec::new();
+            extend_tau_table(&mut fresh, limit);
+            assert_eq!(extended, fresh);
+        });
+        (1..=2049u64).for_each(|n| assert_eq!(extended[n as usize] as u64, divisor_count(n)));
+    }
+
+    #[test]
+    fn can_find_first_triangular_number_with_over_500_divisors() {
+        assert_eq!(
+            first_triangular_with_more_divisors_than(500),
+            (12_375, 76_576_500)
+        );
+    }
+
+    #[test]
+    fn can_find_first_triangular_number_with_over_1000_divisors() {
+        assert_eq!(
+            first_triangular_with_more_divisors_than(1000),
+            (41_040, 842_161_320)
+        );
+    }
+
+    #[test]
+    fn can_find_first_triangular_number_with_over_5000_divisors() {
+        assert_eq!(
+            first_triangular_with_more_divisors_than(5000),
+            (2_203_200, 2_427_046_221_600)
+        );
     }
 
     #[test]